@@ -1,24 +1,150 @@
+use crate::history::{Command, History, Parameter};
+use crate::math::basis::Basis;
 use crate::math::bezier3::Bezier3;
+use crate::math::boolean::BooleanOp;
+use crate::math::matrix2x2::Matrix2x2;
+use crate::math::path::Path;
+use crate::snapping::SnapOptions;
 use crate::{Vec2, APP_SIG};
-use druid::{Data, Rect};
+use druid::{Color, Data, Lens, Rect};
 use preferences::Preferences;
+use std::sync::Arc;
 
-#[derive(Clone, Data)]
+// Pending affine transform entered in the side panel, applied about the centre of the
+// spline's bounding box. Angles are in degrees.
+#[derive(Clone, Data, Lens)]
+pub struct TransformParams {
+    pub translate_x: f64,
+    pub translate_y: f64,
+    pub rotation: f64,
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub skew_x: f64,
+    pub skew_y: f64,
+}
+
+impl TransformParams {
+    pub fn identity() -> Self {
+        Self {
+            translate_x: 0.,
+            translate_y: 0.,
+            rotation: 0.,
+            scale_x: 1.,
+            scale_y: 1.,
+            skew_x: 0.,
+            skew_y: 0.,
+        }
+    }
+
+    // Scale and skew first, then rotate
+    pub fn matrix(&self) -> Matrix2x2<f32> {
+        let rotation = Matrix2x2::identity().rotation((self.rotation as f32).to_radians());
+        let stretch = Matrix2x2::identity()
+            .scale_xy(self.scale_x as f32, self.scale_y as f32)
+            .skew(
+                (self.skew_x as f32).to_radians(),
+                (self.skew_y as f32).to_radians(),
+            );
+        rotation * stretch
+    }
+
+    pub fn translation(&self) -> Vec2<f32> {
+        Vec2::new(self.translate_x as f32, self.translate_y as f32)
+    }
+}
+
+// Offset curve constructions that can be drawn alongside the spline
+#[derive(Clone, Copy, Data, PartialEq, Debug)]
+pub enum OffsetAlgorithm {
+    Levien,
+    Tiller,
+    Klass,
+}
+
+impl OffsetAlgorithm {
+    pub const ALL: [OffsetAlgorithm; 3] = [
+        OffsetAlgorithm::Levien,
+        OffsetAlgorithm::Tiller,
+        OffsetAlgorithm::Klass,
+    ];
+
+    pub fn offset(&self, curve: &Bezier3, d: f32) -> Bezier3 {
+        match self {
+            OffsetAlgorithm::Levien => curve.offset_levien(d),
+            OffsetAlgorithm::Tiller => curve.offset_tiller(d),
+            OffsetAlgorithm::Klass => curve.offset_klass(d),
+        }
+    }
+}
+
+// Side of the spline to offset to, looking along its direction. Left is a positive
+// offset distance.
+#[derive(Clone, Copy, Data, PartialEq, Debug)]
+pub enum OffsetSide {
+    Left,
+    Right,
+    Both,
+}
+
+impl OffsetSide {
+    pub fn distances(&self, d: f32) -> Vec<f32> {
+        match self {
+            OffsetSide::Left => vec![d],
+            OffsetSide::Right => vec![-d],
+            OffsetSide::Both => vec![d, -d],
+        }
+    }
+}
+
+#[derive(Clone, Data, Lens)]
+pub struct OffsetStyle {
+    pub enabled: bool,
+    pub color: Color,
+}
+
+#[derive(Clone, Data, Lens)]
 pub struct AppData {
-    #[data(ignore)]
-    pub spline: Vec<Bezier3>,
-    #[data(ignore)]
-    pub offset: f32,
+    #[data(same_fn = "PartialEq::eq")]
+    pub spline: Path,
+    pub offset: f64,
+    pub offset_side: OffsetSide,
+    pub levien: OffsetStyle,
+    pub tiller: OffsetStyle,
+    pub klass: OffsetStyle,
     pub viewport: Rect,
+    pub curvature_overlay: bool,
+    pub comb_scale: f64,
+    pub comb_density: usize,
+    pub show_info: bool,
+    pub show_inspector: bool,
+    #[data(same_fn = "PartialEq::eq")]
+    pub boolean_op: Option<BooleanOp>,
+    pub transform_mode: bool,
+    pub transform: TransformParams,
+    #[data(same_fn = "PartialEq::eq")]
+    pub control_style: Basis,
+    // Selected control handle as (segment, handle index)
+    pub selection: Option<(usize, usize)>,
+    pub show_grid: bool,
+    pub show_minor_grid: bool,
+    pub show_tick_labels: bool,
+    pub show_origin: bool,
+    // Draw curve space with equal scales on both axes
+    pub equal_aspect: bool,
+    pub snap: SnapOptions,
+    pub history: Arc<History>,
 }
 
 impl AppData {
     pub fn new() -> Self {
-        let spline: Vec<Bezier3>;
-        let result = Vec::<Bezier3>::load(&APP_SIG, "saved_spline");
+        let spline: Path;
+        let result = Path::load(&APP_SIG, "saved_spline");
 
-        if let Ok(splines) = result {
-            spline = splines.into();
+        if let Ok(path) = result {
+            spline = path;
+        } else if let Ok(segments) = Vec::<Bezier3>::load(&APP_SIG, "saved_spline") {
+            // Splines saved before paths existed are a bare list of segments
+            spline = Path::new(segments, false);
         } else {
             let curve_points: Vec<Vec2<f32>> = vec![
                 Vec2::new(0.2, 0.2),
@@ -26,12 +152,258 @@ impl AppData {
                 Vec2::new(0.4, 0.4),
                 Vec2::new(0.9, 0.8),
             ];
-            spline = vec![Bezier3::new(curve_points)];
+            spline = Path::new(vec![Bezier3::new(curve_points)], false);
         }
+
+        // Keep the saved history only if it leads up to the saved spline
+        let history = match History::load(&APP_SIG, "saved_history") {
+            Ok(history) if history.last_spline().is_none_or(|last| *last == spline) => history,
+            _ => History::default(),
+        };
         Self {
             spline,
-            offset: 0.,
+            offset: 0.075,
+            offset_side: OffsetSide::Both,
+            levien: OffsetStyle {
+                enabled: true,
+                color: Color::rgb8(0, 128, 128),
+            },
+            tiller: OffsetStyle {
+                enabled: true,
+                color: Color::rgb8(200, 90, 0),
+            },
+            klass: OffsetStyle {
+                enabled: false,
+                color: Color::rgb8(120, 60, 180),
+            },
             viewport: Rect::ZERO,
+            curvature_overlay: false,
+            comb_scale: 0.01,
+            comb_density: 64,
+            show_info: true,
+            show_inspector: true,
+            boolean_op: None,
+            transform_mode: false,
+            transform: TransformParams::identity(),
+            control_style: Basis::Bezier,
+            selection: None,
+            show_grid: true,
+            show_minor_grid: true,
+            show_tick_labels: true,
+            show_origin: false,
+            equal_aspect: true,
+            snap: SnapOptions::default(),
+            history: Arc::new(history),
+        }
+    }
+
+    pub fn offset_style(&self, algorithm: OffsetAlgorithm) -> &OffsetStyle {
+        match algorithm {
+            OffsetAlgorithm::Levien => &self.levien,
+            OffsetAlgorithm::Tiller => &self.tiller,
+            OffsetAlgorithm::Klass => &self.klass,
+        }
+    }
+
+    // The enabled offset curves to draw, as (algorithm, signed distance)
+    pub fn offsets(&self) -> Vec<(OffsetAlgorithm, f32)> {
+        let distances = self.offset_side.distances(self.offset as f32);
+        OffsetAlgorithm::ALL
+            .iter()
+            .filter(|algorithm| self.offset_style(**algorithm).enabled)
+            .flat_map(|algorithm| distances.iter().map(move |d| (*algorithm, *d)))
+            .collect()
+    }
+
+    // Record the change to the spline since it was `before` as an undoable command
+    pub fn record(&mut self, name: &str, before: Path) {
+        if before != self.spline {
+            Arc::make_mut(&mut self.history).push(Command::Spline {
+                name: name.to_string(),
+                before,
+                after: self.spline.clone(),
+            });
+        }
+    }
+
+    // As record, but merged with an earlier command of the same name from the same
+    // interaction
+    pub fn record_merged(&mut self, name: &str, before: Path) {
+        if before != self.spline {
+            Arc::make_mut(&mut self.history).push_merged(Command::Spline {
+                name: name.to_string(),
+                before,
+                after: self.spline.clone(),
+            });
+        }
+    }
+
+    pub fn parameter(&self, parameter: Parameter) -> f64 {
+        match parameter {
+            Parameter::Offset => self.offset,
+            Parameter::CombScale => self.comb_scale,
+            Parameter::CombDensity => self.comb_density as f64,
         }
     }
+
+    fn apply_parameter(&mut self, parameter: Parameter, value: f64) {
+        match parameter {
+            Parameter::Offset => self.offset = value,
+            Parameter::CombScale => self.comb_scale = value,
+            Parameter::CombDensity => self.comb_density = value as usize,
+        }
+    }
+
+    // Change a parameter as an undoable command
+    pub fn set_parameter(&mut self, parameter: Parameter, value: f64) {
+        let before = self.parameter(parameter);
+        if before != value {
+            self.apply_parameter(parameter, value);
+            Arc::make_mut(&mut self.history).push(Command::Parameter {
+                parameter,
+                before,
+                after: value,
+            });
+        }
+    }
+
+    // As set_parameter, but merged with earlier changes from the same interaction
+    pub fn set_parameter_merged(&mut self, parameter: Parameter, value: f64) {
+        let before = self.parameter(parameter);
+        if before != value {
+            self.apply_parameter(parameter, value);
+            Arc::make_mut(&mut self.history).push_merged(Command::Parameter {
+                parameter,
+                before,
+                after: value,
+            });
+        }
+    }
+
+    // Finish the interaction in progress, so that its next change is a new undo step
+    pub fn end_interaction(&mut self) {
+        if self.history.is_open() {
+            Arc::make_mut(&mut self.history).close();
+        }
+    }
+
+    fn apply_spline(&mut self, spline: Path) {
+        self.spline = spline;
+        // Drop a selection the restored spline no longer has
+        if self.selection.is_some_and(|(s, _)| s >= self.spline.len()) {
+            self.selection = None;
+        }
+    }
+
+    pub fn undo(&mut self) {
+        match Arc::make_mut(&mut self.history).undo() {
+            Some(Command::Spline { before, .. }) => self.apply_spline(before),
+            Some(Command::Parameter {
+                parameter, before, ..
+            }) => self.apply_parameter(parameter, before),
+            None => {}
+        }
+    }
+
+    pub fn redo(&mut self) {
+        match Arc::make_mut(&mut self.history).redo() {
+            Some(Command::Spline { after, .. }) => self.apply_spline(after),
+            Some(Command::Parameter {
+                parameter, after, ..
+            }) => self.apply_parameter(parameter, after),
+            None => {}
+        }
+    }
+
+    // Scale the viewport by factor about a fixed point in curve space, zooming out for
+    // factors above one
+    pub fn zoom(&mut self, about: Vec2<f32>, factor: f64) {
+        let (x, y) = (about.x as f64, about.y as f64);
+        self.viewport = Rect::new(
+            x + (self.viewport.x0 - x) * factor,
+            y + (self.viewport.y0 - y) * factor,
+            x + (self.viewport.x1 - x) * factor,
+            y + (self.viewport.y1 - y) * factor,
+        );
+    }
+
+    // Frame bounds [x_min, y_min, x_max, y_max] with a margin around them
+    pub fn zoom_to(&mut self, bounds: [f32; 4]) {
+        let rect = Rect::new(
+            bounds[0] as f64,
+            bounds[1] as f64,
+            bounds[2] as f64,
+            bounds[3] as f64,
+        );
+        // Keep points and straight lines from collapsing the viewport
+        let size = rect.width().max(rect.height()).max(1e-3);
+        let margin = 0.1 * size;
+        self.viewport = Rect::from_center_size(
+            rect.center(),
+            (
+                rect.width().max(size * 0.25) + 2. * margin,
+                rect.height().max(size * 0.25) + 2. * margin,
+            ),
+        );
+    }
+
+    // Frame the spline and its offsets
+    pub fn zoom_to_fit(&mut self) {
+        let offset = self.offset.abs() as f32;
+        if let Some(b) = self.spline.bounds() {
+            self.zoom_to([b[0] - offset, b[1] - offset, b[2] + offset, b[3] + offset]);
+        }
+    }
+
+    // Frame the segment holding the selected handle, or everything with no selection
+    pub fn zoom_to_selection(&mut self) {
+        let bounds = self
+            .selection
+            .and_then(|(s, _)| self.spline.segments.get(s))
+            .map(|segment| segment.bounds());
+        match bounds {
+            Some(bounds) => self.zoom_to(bounds),
+            None => self.zoom_to_fit(),
+        }
+    }
+
+    // Centre of the spline's bounding box, the pivot for panel transforms
+    pub fn pivot(&self) -> Vec2<f32> {
+        match self.spline.bounds() {
+            Some(b) => Vec2::new((b[0] + b[2]) / 2., (b[1] + b[3]) / 2.),
+            None => Vec2::new(0., 0.),
+        }
+    }
+
+    // Apply the pending transform to the spline and reset the parameters. A transform that
+    // doesn't map to finite numbers, say from a huge scale, would ruin the spline, so it is
+    // left pending instead.
+    pub fn apply_transform(&mut self) {
+        let (m, translation) = (self.transform.matrix(), self.transform.translation());
+        if ![m.a, m.b, m.c, m.d, translation.x, translation.y]
+            .iter()
+            .all(|x| x.is_finite())
+        {
+            return;
+        }
+        let before = self.spline.clone();
+        self.spline = self.spline.transform_about(m, self.pivot(), translation);
+        self.transform = TransformParams::identity();
+        self.record("Transform", before);
+    }
+
+    // Mirror the spline across the vertical (horizontal = true) or horizontal axis
+    // through its centre
+    pub fn flip(&mut self, horizontal: bool) {
+        let m = if horizontal {
+            Matrix2x2::identity().scale_xy(-1., 1.)
+        } else {
+            Matrix2x2::identity().scale_xy(1., -1.)
+        };
+        let before = self.spline.clone();
+        self.spline = self
+            .spline
+            .transform_about(m, self.pivot(), Vec2::new(0., 0.));
+        self.record("Flip", before);
+    }
 }
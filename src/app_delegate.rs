@@ -22,5 +22,9 @@ impl AppDelegate<AppData> for Delegate {
         if !save_result.is_ok() {
             println!("Error saving current spline: {:?}", save_result);
         }
+        let save_result = data.history.as_ref().save(&APP_SIG, "saved_history");
+        if !save_result.is_ok() {
+            println!("Error saving edit history: {:?}", save_result);
+        }
     }
 }
@@ -1,101 +1,231 @@
 use druid::piet::{StrokeStyle, TextLayout, TextLayoutBuilder};
 use druid::{
-    kurbo::Line, piet::Text, BoxConstraints, Color, Env, Event, EventCtx, FontFamily, LayoutCtx,
-    LifeCycle, LifeCycleCtx, PaintCtx, Point, Rect, RenderContext, Size, UpdateCtx, Widget,
+    kurbo::{BezPath, Circle, Line},
+    piet::Text,
+    BoxConstraints, Color, Data, Env, Event, EventCtx, FontFamily, KbKey, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, Rect, RenderContext, Size, UpdateCtx, Widget,
 };
 
-use crate::app_data::AppData;
+use crate::app_data::{AppData, OffsetAlgorithm};
+use crate::history::Parameter;
+use crate::math::basis::Basis;
 use crate::math::bezier3::Bezier3;
+use crate::math::boolean::{boolean, BooleanOp};
+use crate::math::matrix2x2::Matrix2x2;
+use crate::math::path::Path;
+use crate::math::ticks;
 use crate::math::vec2::Vec2;
+use crate::snapping::{self, Snap};
+use crate::view_transform::ViewTransform;
+
+// Screen distance from the top of the bounding box to the rotation handle
+const ROTATE_HANDLE_OFFSET: f64 = 25.;
+
+// Parts of the transform gizmo. Scale handles are identified by the side of the bounding
+// box they sit on, -1, 0 or 1 along each axis.
+#[derive(Clone, Copy, PartialEq)]
+enum GizmoHandle {
+    Move,
+    Rotate,
+    Scale(i8, i8),
+}
+
+// A gizmo drag in progress, holding the spline and its bounds from when it began
+struct GizmoDrag {
+    handle: GizmoHandle,
+    start: Vec2<f32>,
+    original: Path,
+    bounds: [f32; 4],
+}
 
 pub struct ContainerWidget {
-    canvas_rect: Rect,
-    canvas_viewport_screen: Rect,
-    canvas_viewport_curve: Rect,
+    view: ViewTransform,
     dragging: bool,
     drag_start: Point,
     drag_pos: Point,
-    drag_object: Option<usize>,
+    drag_object: Option<(usize, usize)>,
+    hover_pos: Option<Point>,
+    hover_handle: Option<(usize, usize)>,
+    boolean_operands: Vec<usize>,
+    gizmo_drag: Option<GizmoDrag>,
+    // Pan in progress, holding where it started and the viewport at that time
+    pan: Option<(Point, Rect)>,
+    space_down: bool,
+    // What the dragged handle last snapped to
+    snap: Option<Snap>,
+    // The spline before the current point drag, recorded for undo when it ends
+    drag_original: Option<Path>,
 }
 
 impl ContainerWidget {
     pub fn new() -> Self {
         Self {
-            canvas_rect: Rect::ZERO,
-            canvas_viewport_screen: Rect::ZERO,
-            canvas_viewport_curve: Rect::ZERO,
+            view: ViewTransform::new(Rect::ZERO, Rect::ZERO, false),
             dragging: false,
             drag_start: Point::ZERO,
             drag_pos: Point::ZERO,
             drag_object: None,
+            hover_pos: None,
+            hover_handle: None,
+            boolean_operands: Vec::new(),
+            gizmo_drag: None,
+            pan: None,
+            space_down: false,
+            snap: None,
+            drag_original: None,
         }
     }
 
-    fn map_range(
-        num: f32,
-        domain_min: f32,
-        domain_max: f32,
-        range_min: f32,
-        range_max: f32,
-    ) -> f32 {
-        (num - domain_min) * (range_max - range_min) / (domain_max - domain_min) + range_min
-    }
-
     pub fn map_curvespace_to_screenspace(&self, source_vec: &Vec2<f32>) -> Point {
-        Point::new(
-            ContainerWidget::map_range(
-                source_vec.x,
-                self.canvas_viewport_curve.x0 as f32,
-                self.canvas_viewport_curve.x1 as f32,
-                self.canvas_viewport_screen.x0 as f32,
-                self.canvas_viewport_screen.x1 as f32,
-            )
-            .into(),
-            ContainerWidget::map_range(
-                source_vec.y,
-                self.canvas_viewport_curve.y0 as f32,
-                self.canvas_viewport_curve.y1 as f32,
-                self.canvas_viewport_screen.y1 as f32,
-                self.canvas_viewport_screen.y0 as f32,
-            )
-            .into(),
-        )
+        self.view.to_screen(source_vec)
     }
 
     pub fn map_screenspace_to_curvespace(&self, source_point: Point) -> Vec2<f32> {
-        Vec2::new(
-            ContainerWidget::map_range(
-                source_point.x as f32,
-                (self.canvas_rect.x0 - (self.canvas_rect.x0 - self.canvas_viewport_screen.x0))
-                    as f32,
-                (self.canvas_rect.x1 - (self.canvas_rect.x1 - self.canvas_viewport_screen.x1))
-                    as f32,
-                self.canvas_viewport_curve.x0 as f32,
-                self.canvas_viewport_curve.x1 as f32,
-            ),
-            ContainerWidget::map_range(
-                source_point.y as f32,
-                (self.canvas_rect.y0 - (self.canvas_rect.y0 - self.canvas_viewport_screen.y0))
-                    as f32,
-                (self.canvas_rect.y1 - (self.canvas_rect.y1 - self.canvas_viewport_screen.y1))
-                    as f32,
-                self.canvas_viewport_curve.y1 as f32,
-                self.canvas_viewport_curve.y0 as f32,
-            ),
-        )
+        self.view.to_curve(source_point)
     }
 
-    pub fn drag_point(&mut self, curve: &mut Bezier3) {
-        if let Some(i) = self.drag_object {
-            curve.control_points[i] = self.map_screenspace_to_curvespace(self.drag_pos);
+    pub fn drag_point(&mut self, data: &mut AppData, snap_enabled: bool) {
+        // A click without movement only selects
+        if self.drag_pos == self.drag_start {
+            return;
+        }
+        if let Some((s, i)) = self.drag_object {
+            let mut position = self.map_screenspace_to_curvespace(self.drag_pos);
+            self.snap = None;
+            if snap_enabled {
+                // Snap within 8 pixels, and to the minor grid lines
+                let (scale_x, scale_y) = self.view.scale();
+                let (step_x, step_y) = self.tick_steps();
+                (position, self.snap) = snapping::snap(
+                    &data.spline,
+                    data.control_style,
+                    (s, i),
+                    position,
+                    &data.snap,
+                    Vec2::new(
+                        ticks::minor_step(step_x) as f32,
+                        ticks::minor_step(step_y) as f32,
+                    ),
+                    (8. / scale_x.min(scale_y)) as f32,
+                );
+            }
+            data.spline.move_handle(s, i, data.control_style, position);
+        }
+    }
+
+    // Shift the viewport so the curve point under the pan's start follows the mouse
+    fn drag_pan(&self, data: &mut AppData, pos: Point) {
+        if let Some((start, viewport)) = self.pan {
+            let screen = self.view.screen;
+            let dx = (pos.x - start.x) * viewport.width() / screen.width();
+            let dy = (pos.y - start.y) * viewport.height() / screen.height();
+            data.viewport = viewport + druid::Vec2::new(-dx, dy);
+        }
+    }
+
+    // The (segment, handle) nearest to pos within reach of the mouse. On a tie, such as the
+    // shared endpoint of two segments, the selected segment wins.
+    fn pick_handle(&self, data: &AppData, pos: Point) -> Option<(usize, usize)> {
+        let selected_segment = data.selection.map(|(s, _)| s);
+        let mut best: Option<((usize, usize), f64)> = None;
+        for (s, segment) in data.spline.segments.iter().enumerate() {
+            for (i, p) in segment
+                .control_handles(data.control_style)
+                .iter()
+                .enumerate()
+            {
+                let d = self.map_curvespace_to_screenspace(p).distance(pos);
+                if d >= 10. {
+                    continue;
+                }
+                let better = match best {
+                    None => true,
+                    Some((_, d_best)) if d < d_best - 0.5 => true,
+                    Some((_, d_best)) => d <= d_best + 0.5 && selected_segment == Some(s),
+                };
+                if better {
+                    best = Some(((s, i), d));
+                }
+            }
+        }
+        best.map(|(handle, _)| handle)
+    }
+
+    // The path node under the selected handle, if it is an endpoint. B-spline and
+    // Catmull-Rom handles are never on the curve, so they don't select nodes.
+    fn selected_node(data: &AppData) -> Option<usize> {
+        let (s, i) = data.selection?;
+        let node = match (data.control_style, i) {
+            (Basis::Bezier | Basis::Hermite, 0) => s,
+            (Basis::Bezier, 3) | (Basis::Hermite, 1) => s + 1,
+            _ => return None,
+        };
+        if data.spline.closed {
+            Some(node % data.spline.len())
+        } else {
+            Some(node)
+        }
+    }
+
+    // Split the segment under pos at the nearest point, selecting the new node
+    fn insert_point(&self, data: &mut AppData, pos: Point) -> bool {
+        let p = self.map_screenspace_to_curvespace(pos);
+        match data.spline.nearest_point(p) {
+            Some((s, t, _))
+                if self
+                    .map_curvespace_to_screenspace(&data.spline.segments[s].get_point(t))
+                    .distance(pos)
+                    < 8. =>
+            {
+                let before = data.spline.clone();
+                data.spline.insert_point(s, t);
+                data.record("Insert point", before);
+                data.selection = Some((s + 1, 0));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Ring the hovered and selected control handles
+    pub fn render_handle_highlights(&self, ctx: &mut impl RenderContext, data: &AppData) {
+        let handle_point = |(s, i): (usize, usize)| {
+            data.spline.segments.get(s).map(|segment| {
+                self.map_curvespace_to_screenspace(&segment.control_handles(data.control_style)[i])
+            })
+        };
+
+        if let Some(p) = self.hover_handle.and_then(handle_point) {
+            ctx.stroke(Circle::new(p, 7.), &Color::GRAY, 1.);
+        }
+        if let Some(p) = data.selection.and_then(handle_point) {
+            ctx.stroke(Circle::new(p, 6.), &Color::rgb8(255, 140, 0), 2.);
+        }
+
+        // Emphasise the rest of the selected segment's handles
+        if let Some(segment) = data
+            .selection
+            .and_then(|(s, _)| data.spline.segments.get(s))
+        {
+            for p in segment.control_handles(data.control_style).iter() {
+                ctx.stroke(
+                    Rect::from_center_size(
+                        self.map_curvespace_to_screenspace(p),
+                        Size::new(9., 9.),
+                    ),
+                    &Color::rgb8(255, 140, 0),
+                    1.,
+                );
+            }
         }
     }
 
     pub fn render_curve(
         &mut self,
         ctx: &mut impl RenderContext,
-        _data: &AppData,
+        data: &AppData,
         curve: &Bezier3,
+        color: &Color,
         show_controls: bool,
     ) {
         // Retrieve curve samples
@@ -109,11 +239,7 @@ impl ContainerWidget {
 
         // Plot curve by drawing lines between sample points
         for i in 0..screen_points.len() - 1 {
-            ctx.stroke(
-                Line::new(screen_points[i], screen_points[i + 1]),
-                &Color::TEAL,
-                1.,
-            );
+            ctx.stroke(Line::new(screen_points[i], screen_points[i + 1]), color, 1.);
         }
 
         if show_controls {
@@ -122,18 +248,20 @@ impl ContainerWidget {
                 ctx.fill(Rect::from_center_size(p, Size::new(2., 2.)), &Color::RED);
             }
 
-            // Draw control quad edges
+            // Draw the controls in the chosen representation
+            let handles = curve.control_handles(data.control_style);
+            let edges: &[(usize, usize)] = match data.control_style {
+                // Tangent handles hang off their endpoints
+                Basis::Hermite => &[(0, 2), (1, 3)],
+                // Otherwise draw the control quad edges
+                _ => &[(0, 1), (1, 2), (2, 3), (3, 0)],
+            };
             let _hull_stroke_style = StrokeStyle::new().dash_pattern(&[6., 3.]);
-            for i in 0..curve.control_points.len() {
-                let j = if i == curve.control_points.len() - 1 {
-                    0
-                } else {
-                    i + 1
-                };
+            for &(i, j) in edges {
                 ctx.stroke(
                     Line::new(
-                        self.map_curvespace_to_screenspace(&curve.control_points[i]),
-                        self.map_curvespace_to_screenspace(&curve.control_points[j]),
+                        self.map_curvespace_to_screenspace(&handles[i]),
+                        self.map_curvespace_to_screenspace(&handles[j]),
                     ),
                     &Color::GRAY,
                     1.,
@@ -141,7 +269,7 @@ impl ContainerWidget {
             }
 
             // Draw control points
-            for p in &curve.control_points {
+            for p in &handles {
                 ctx.fill(
                     Rect::from_center_size(
                         self.map_curvespace_to_screenspace(p),
@@ -152,6 +280,685 @@ impl ContainerWidget {
             }
         }
     }
+
+    // The source path followed by each enabled offset, with the colours to draw them in
+    pub fn shapes(data: &AppData) -> Vec<(Path, Color)> {
+        let mut shapes = vec![(data.spline.clone(), Color::TEAL)];
+        for (algorithm, d) in data.offsets() {
+            // Each segment is offset on its own; joining them up would bend the offsets
+            // wherever the source path has a corner
+            let segments = data
+                .spline
+                .segments
+                .iter()
+                .map(|segment| algorithm.offset(segment, d))
+                .collect();
+            let path = Path::new(segments, data.spline.closed);
+            shapes.push((path, data.offset_style(algorithm).color));
+        }
+        shapes
+    }
+
+    // Key to the offset curves in the bottom right corner of the plot area
+    pub fn render_legend(&self, ctx: &mut impl RenderContext, data: &AppData) {
+        let mut entries = vec![("Source".to_string(), Color::TEAL)];
+        for algorithm in OffsetAlgorithm::ALL {
+            let style = data.offset_style(algorithm);
+            if style.enabled {
+                entries.push((
+                    format!(
+                        "{:?}, {:?}, d = {:.3}",
+                        algorithm, data.offset_side, data.offset
+                    ),
+                    style.color,
+                ));
+            }
+        }
+
+        let layouts: Vec<_> = entries
+            .into_iter()
+            .map(|(text, color)| {
+                let layout = ctx
+                    .text()
+                    .new_text_layout(text)
+                    .font(FontFamily::MONOSPACE, 10.0)
+                    .text_color(Color::BLACK)
+                    .build()
+                    .unwrap();
+                (layout, color)
+            })
+            .collect();
+        let width = layouts
+            .iter()
+            .map(|(layout, _)| layout.size().width)
+            .fold(0., f64::max);
+
+        let screen = self.view.screen;
+        let mut y = screen.y1 - 14. * layouts.len() as f64 - 10.;
+        let x = screen.x1 - width - 34.;
+        let panel = Rect::new(x, y, screen.x1 - 10., screen.y1 - 10.).inset(4.);
+        ctx.fill(panel, &Color::rgba8(255, 255, 255, 220));
+        ctx.stroke(panel, &Color::GRAY, 1.);
+        for (layout, color) in layouts {
+            let mid = y + layout.size().height / 2.;
+            ctx.stroke(Line::new((x, mid), (x + 18., mid)), &color, 2.);
+            ctx.draw_text(&layout, (x + 24., y));
+            y += 14.;
+        }
+    }
+
+    // Index of the shape with a curve within 10px of a screen space point
+    fn pick_shape(&self, data: &AppData, pos: Point) -> Option<usize> {
+        let target = self.map_screenspace_to_curvespace(pos);
+        ContainerWidget::shapes(data)
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (shape, _))| shape.segments.iter().map(move |s| (i, s)))
+            .map(|(i, s)| {
+                let (t, _) = s.nearest_point(target);
+                (
+                    i,
+                    self.map_curvespace_to_screenspace(&s.get_point(t))
+                        .distance(pos),
+                )
+            })
+            .filter(|(_, d)| *d < 10.)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    // Build a screen space path through a chain of connected curves
+    fn screen_path(&self, segments: &[Bezier3]) -> BezPath {
+        // The curve space mapping is affine, so mapping the control points maps the curves
+        let mut bez_path = BezPath::new();
+        bez_path.move_to(self.map_curvespace_to_screenspace(&segments[0].control_points[0]));
+        for segment in segments {
+            let p: Vec<Point> = segment
+                .control_points
+                .iter()
+                .map(|p| self.map_curvespace_to_screenspace(p))
+                .collect();
+            bez_path.curve_to(p[1], p[2], p[3]);
+        }
+        bez_path.close_path();
+        bez_path
+    }
+
+    pub fn render_fill(&mut self, ctx: &mut impl RenderContext, path: &Path, color: &Color) {
+        let segments = path.closed_segments();
+        if segments.is_empty() {
+            return;
+        }
+        ctx.fill(self.screen_path(&segments), color);
+
+        // Show the implicit closing segment
+        if segments.len() > path.len() {
+            let closing = &segments[segments.len() - 1];
+            ctx.stroke_styled(
+                Line::new(
+                    self.map_curvespace_to_screenspace(&closing.control_points[0]),
+                    self.map_curvespace_to_screenspace(&closing.control_points[3]),
+                ),
+                &Color::TEAL,
+                1.,
+                &StrokeStyle::new().dash_pattern(&[4., 4.]),
+            );
+        }
+    }
+
+    pub fn render_boolean_preview(
+        &mut self,
+        ctx: &mut impl RenderContext,
+        shapes: &[(Path, Color)],
+        op: BooleanOp,
+    ) {
+        // Highlight the chosen operands, which may have gone if their offset was disabled
+        for &i in &self.boolean_operands {
+            if let Some((shape, _)) = shapes.get(i).filter(|(shape, _)| !shape.is_empty()) {
+                let outline = self.screen_path(&shape.closed_segments());
+                ctx.stroke(outline, &Color::rgb8(255, 140, 0), 3.);
+            }
+        }
+
+        if let [a, b] = self.boolean_operands[..] {
+            if let (Some((a, _)), Some((b, _))) = (shapes.get(a), shapes.get(b)) {
+                for result in boolean(a, b, op) {
+                    let outline = self.screen_path(&result.segments);
+                    ctx.fill(outline.clone(), &Color::rgba8(30, 90, 200, 80));
+                    ctx.stroke(outline, &Color::NAVY, 1.5);
+                }
+            }
+        }
+    }
+
+    pub fn render_analysis(&mut self, ctx: &mut impl RenderContext, curve: &Bezier3) {
+        // Mark inflection points
+        for t in curve.inflections() {
+            let p = self.map_curvespace_to_screenspace(&curve.get_point(t));
+            ctx.stroke(Circle::new(p, 4.), &Color::GREEN, 1.5);
+        }
+
+        // Mark the cusp
+        if let Some(t) = curve.cusp() {
+            let p = self.map_curvespace_to_screenspace(&curve.get_point(t));
+            ctx.fill(Circle::new(p, 4.), &Color::rgb8(255, 140, 0));
+        }
+
+        // Mark the self-intersection of a loop
+        if let Some((s, _)) = curve.self_intersection() {
+            let p = self.map_curvespace_to_screenspace(&curve.get_point(s));
+            ctx.stroke(Circle::new(p, 6.), &Color::PURPLE, 1.5);
+        }
+    }
+
+    pub fn render_curvature_comb(
+        &mut self,
+        ctx: &mut impl RenderContext,
+        data: &AppData,
+        curve: &Bezier3,
+    ) {
+        // Spikes point away from the centre of curvature with length proportional to curvature
+        let n = data.comb_density.max(1);
+        let mut tips = Vec::new();
+        for i in 0..n + 1 {
+            let t = i as f32 / n as f32;
+            let p = curve.get_point(t);
+            // At a cusp the tangent vanishes, leaving no normal to draw a spike along
+            let (normal, k) = match curve.tangent(t).normal().try_normalize() {
+                Some(normal) if curve.curvature(t).is_finite() => (normal, curve.curvature(t)),
+                _ => continue,
+            };
+            let tip = p - (data.comb_scale as f32 * k) * normal;
+
+            let p = self.map_curvespace_to_screenspace(&p);
+            let tip = self.map_curvespace_to_screenspace(&tip);
+            ctx.stroke(Line::new(p, tip), &Color::rgba8(128, 0, 128, 128), 1.);
+            tips.push(tip);
+        }
+
+        // Connect the spike tips to show the comb envelope
+        for pair in tips.windows(2) {
+            ctx.stroke(Line::new(pair[0], pair[1]), &Color::PURPLE, 1.);
+        }
+    }
+
+    // The (segment, t) of the source curve point nearest to pos, if within 30px of it
+    fn hovered_point(&self, data: &AppData, pos: Point) -> Option<(usize, f32)> {
+        let target = self.map_screenspace_to_curvespace(pos);
+        let (s, t, _) = data.spline.nearest_point(target)?;
+        let p = data.spline.segments[s].get_point(t);
+        (self.map_curvespace_to_screenspace(&p).distance(pos) <= 30.).then_some((s, t))
+    }
+
+    // A screen space arrow of the given length from a point along a curve space direction
+    fn render_arrow(
+        &self,
+        ctx: &mut impl RenderContext,
+        from: Point,
+        direction: Vec2<f32>,
+        length: f64,
+        color: &Color,
+    ) {
+        // Screen y points down, and the axes may be scaled differently
+        let (scale_x, scale_y) = self.view.scale();
+        let d = druid::Vec2::new(direction.x as f64 * scale_x, -direction.y as f64 * scale_y);
+        if d.hypot() == 0. {
+            return;
+        }
+        let d = d.normalize();
+        let tip = from + d * length;
+        let side = druid::Vec2::new(-d.y, d.x);
+        ctx.stroke(Line::new(from, tip), color, 1.5);
+        ctx.stroke(Line::new(tip, tip - d * 7. + side * 4.), color, 1.5);
+        ctx.stroke(Line::new(tip, tip - d * 7. - side * 4.), color, 1.5);
+    }
+
+    // Tooltip describing the curve under the mouse, with its tangent and normal drawn on.
+    // Offset distances are measured to the shapes paint has already built.
+    pub fn render_inspector(
+        &self,
+        ctx: &mut impl RenderContext,
+        data: &AppData,
+        shapes: &[(Path, Color)],
+    ) {
+        let hover_pos = match self.hover_pos {
+            Some(p) => p,
+            None => return,
+        };
+        let (s, t) = match self.hovered_point(data, hover_pos) {
+            Some(hovered) => hovered,
+            None => return,
+        };
+        let curve = &data.spline.segments[s];
+        let p = curve.get_point(t);
+        let velocity = curve.first_derivative(t);
+        let tangent = velocity.y.atan2(velocity.x).to_degrees();
+
+        let mut text = format!("Segment {}  t = {:.4}\n", s, t);
+        text += &format!("Point     ({:.4}, {:.4})\n", p.x, p.y);
+        text += &format!("Tangent   {:>8.2}°\n", tangent);
+        text += &format!("Speed     {:>9.4}\n", velocity.mag());
+        text += &format!("Curvature {:>9.4}", curve.curvature(t));
+
+        // Distance from the point to each offset curve, ideally the offset distance. An
+        // offset that failed to construct has no meaningful distance and is left out.
+        for ((algorithm, d), (shape, _)) in data.offsets().into_iter().zip(&shapes[1..]) {
+            if let Some((_, _, distance)) = shape
+                .nearest_point(p)
+                .filter(|(_, _, distance)| distance.is_finite())
+            {
+                text += &format!(
+                    "\n{:<7} {:+.3}  {:.4}",
+                    format!("{:?}", algorithm),
+                    d,
+                    distance
+                );
+            }
+        }
+
+        // Tangent and normal arrows at the point
+        let q = self.map_curvespace_to_screenspace(&p);
+        ctx.fill(Circle::new(q, 3.), &Color::NAVY);
+        self.render_arrow(ctx, q, velocity, 40., &Color::NAVY);
+        self.render_arrow(ctx, q, velocity.normal(), 25., &Color::rgb8(200, 0, 200));
+
+        let layout = ctx
+            .text()
+            .new_text_layout(text)
+            .font(FontFamily::MONOSPACE, 10.0)
+            .text_color(Color::BLACK)
+            .build()
+            .unwrap();
+
+        // Sit the tooltip below right of the mouse, kept inside the canvas
+        let size = layout.size();
+        let canvas = self.view.screen;
+        let origin = Point::new(
+            (hover_pos.x + 16.).min(canvas.x1 - size.width - 8.),
+            (hover_pos.y + 16.).min(canvas.y1 - size.height - 8.),
+        );
+        let panel = Rect::from_origin_size(origin, size).inset(4.);
+        ctx.fill(panel, &Color::rgba8(255, 255, 240, 235));
+        ctx.stroke(panel, &Color::GRAY, 1.);
+        ctx.draw_text(&layout, origin);
+    }
+
+    pub fn render_osculating_circle(&mut self, ctx: &mut impl RenderContext, data: &AppData) {
+        let hover_pos = match self.hover_pos {
+            Some(p) => p,
+            None => return,
+        };
+
+        let (curve, t) = match self.hovered_point(data, hover_pos) {
+            Some((s, t)) => (&data.spline.segments[s], t),
+            None => return,
+        };
+        let p = curve.get_point(t);
+
+        let k = curve.curvature(t);
+        if !k.is_finite() || k.abs() < 1e-6 {
+            return;
+        }
+
+        // The centre of the osculating circle is the corresponding point on the evolute
+        let r = 1. / k;
+        let normal = match curve.tangent(t).normal().try_normalize() {
+            Some(normal) => normal,
+            None => return,
+        };
+        let center = p + r * normal;
+
+        // Plot the circle through the curve space mapping so it matches the plotted curve
+        let samples = 64;
+        let circle_points: Vec<Point> = (0..samples + 1)
+            .map(|i| {
+                let theta = 2. * std::f32::consts::PI * i as f32 / samples as f32;
+                let q = center + r.abs() * Vec2::new(theta.cos(), theta.sin());
+                self.map_curvespace_to_screenspace(&q)
+            })
+            .collect();
+        for i in 0..samples {
+            ctx.stroke(
+                Line::new(circle_points[i], circle_points[i + 1]),
+                &Color::rgb8(255, 140, 0),
+                1.,
+            );
+        }
+
+        let p = self.map_curvespace_to_screenspace(&p);
+        let center = self.map_curvespace_to_screenspace(&center);
+        ctx.stroke(Line::new(p, center), &Color::rgba8(255, 140, 0, 128), 1.);
+        ctx.fill(Circle::new(center, 3.), &Color::rgb8(255, 140, 0));
+        ctx.fill(Circle::new(p, 3.), &Color::rgb8(255, 140, 0));
+    }
+
+    pub fn render_info_panel(&mut self, ctx: &mut impl RenderContext, data: &AppData) {
+        let moments = data.spline.moments();
+        let centroid = moments.centroid();
+
+        // Mark the centroid
+        if let Some(c) = centroid {
+            let c = self.map_curvespace_to_screenspace(&c);
+            ctx.stroke(
+                Line::new(c - (5., 0.), c + (5., 0.)),
+                &Color::rgb8(0, 100, 0),
+                1.,
+            );
+            ctx.stroke(
+                Line::new(c - (0., 5.), c + (0., 5.)),
+                &Color::rgb8(0, 100, 0),
+                1.,
+            );
+        }
+
+        let (i_xx, i_xy, i_yy) = moments.central();
+        let mut text = format!("Area      {:>9.5}\n", moments.area);
+        match centroid {
+            Some(c) => text += &format!("Centroid  ({:.4}, {:.4})\n", c.x, c.y),
+            None => text += "Centroid  -\n",
+        }
+        text += &format!("I_xx      {:>9.6}\n", i_xx);
+        text += &format!("I_xy      {:>9.6}\n", i_xy);
+        text += &format!("I_yy      {:>9.6}\n", i_yy);
+        text += &format!("J         {:>9.6}", moments.polar_inertia());
+
+        let layout = ctx
+            .text()
+            .new_text_layout(text)
+            .font(FontFamily::MONOSPACE, 10.0)
+            .text_color(Color::BLACK)
+            .build()
+            .unwrap();
+
+        // Draw the panel in the top right corner of the plot area
+        let size = layout.size();
+        let origin = Point::new(
+            self.view.screen.x1 - size.width - 10.,
+            self.view.screen.y0 + 10.,
+        );
+        let panel = Rect::from_origin_size(origin, size).inset(4.);
+        ctx.fill(panel, &Color::rgba8(255, 255, 255, 220));
+        ctx.stroke(panel, &Color::GRAY, 1.);
+        ctx.draw_text(&layout, origin);
+    }
+
+    // Mark what the dragged handle snapped to
+    pub fn render_snap_indicator(&self, ctx: &mut impl RenderContext, data: &AppData) {
+        let (snap, (s, i)) = match (self.snap, self.drag_object) {
+            (Some(snap), Some(handle)) => (snap, handle),
+            _ => return,
+        };
+        let handle = match data.spline.segments.get(s) {
+            Some(segment) => segment.control_handles(data.control_style)[i],
+            None => return,
+        };
+        let color = Color::rgb8(200, 0, 200);
+        let p = self.map_curvespace_to_screenspace(&handle);
+        let dashed = StrokeStyle::new().dash_pattern(&[4., 3.]);
+
+        match snap {
+            Snap::Point(_) => ctx.stroke(Circle::new(p, 9.), &color, 1.5),
+            Snap::Grid(_) => {
+                ctx.stroke(Line::new(p - (6., 0.), p + (6., 0.)), &color, 1.);
+                ctx.stroke(Line::new(p - (0., 6.), p + (0., 6.)), &color, 1.);
+            }
+            Snap::Tangent { joint, .. } => {
+                // Extend the tangent line through the joint to both sides
+                let joint = self.map_curvespace_to_screenspace(&joint);
+                let reflected = joint + (joint - p);
+                ctx.stroke_styled(Line::new(reflected, p), &color, 1., &dashed);
+                ctx.stroke(Circle::new(joint, 4.), &color, 1.);
+            }
+            Snap::Angle { anchor, degrees } => {
+                let anchor = self.map_curvespace_to_screenspace(&anchor);
+                ctx.stroke_styled(Line::new(anchor, p), &color, 1., &dashed);
+                let label = ctx
+                    .text()
+                    .new_text_layout(format!("{}°", degrees))
+                    .font(FontFamily::MONOSPACE, 10.0)
+                    .text_color(color)
+                    .build()
+                    .unwrap();
+                ctx.draw_text(&label, p + (8., 8.));
+            }
+        }
+    }
+
+    // Nice tick spacings in x and y, roughly one major tick per 80 pixels
+    fn tick_steps(&self) -> (f64, f64) {
+        let screen = self.view.screen;
+        let viewport = self.view.curve;
+        (
+            ticks::nice_step(viewport.width(), (screen.width() / 80.).ceil() as usize),
+            ticks::nice_step(viewport.height(), (screen.height() / 80.).ceil() as usize),
+        )
+    }
+
+    fn screen_x(&self, x: f64) -> f64 {
+        self.map_curvespace_to_screenspace(&Vec2::new(x as f32, 0.))
+            .x
+    }
+
+    fn screen_y(&self, y: f64) -> f64 {
+        self.map_curvespace_to_screenspace(&Vec2::new(0., y as f32))
+            .y
+    }
+
+    // Major and minor grid lines and the origin crosshair, to be clipped to the plot area
+    pub fn render_grid(&self, ctx: &mut impl RenderContext, data: &AppData) {
+        let screen = self.view.screen;
+        let viewport = self.view.curve;
+        let (step_x, step_y) = self.tick_steps();
+        let vertical =
+            |x: f64| Line::new((self.screen_x(x), screen.y0), (self.screen_x(x), screen.y1));
+        let horizontal =
+            |y: f64| Line::new((screen.x0, self.screen_y(y)), (screen.x1, self.screen_y(y)));
+
+        if data.show_grid && data.show_minor_grid {
+            let minor = Color::rgb8(240, 240, 240);
+            for x in ticks::ticks(viewport.x0, viewport.x1, ticks::minor_step(step_x)) {
+                ctx.stroke(vertical(x), &minor, 1.);
+            }
+            for y in ticks::ticks(viewport.y0, viewport.y1, ticks::minor_step(step_y)) {
+                ctx.stroke(horizontal(y), &minor, 1.);
+            }
+        }
+
+        if data.show_grid {
+            let major = Color::rgb8(215, 215, 215);
+            for x in ticks::ticks(viewport.x0, viewport.x1, step_x) {
+                ctx.stroke(vertical(x), &major, 1.);
+            }
+            for y in ticks::ticks(viewport.y0, viewport.y1, step_y) {
+                ctx.stroke(horizontal(y), &major, 1.);
+            }
+        }
+
+        // Crosshair through the origin, when it is in view
+        if data.show_origin {
+            let axis = Color::rgb8(120, 120, 120);
+            if viewport.x0 <= 0. && 0. <= viewport.x1 {
+                ctx.stroke(vertical(0.), &axis, 1.);
+            }
+            if viewport.y0 <= 0. && 0. <= viewport.y1 {
+                ctx.stroke(horizontal(0.), &axis, 1.);
+            }
+        }
+    }
+
+    // Labelled major ticks along the bottom and left edges of the plot area
+    pub fn render_tick_labels(&self, ctx: &mut impl RenderContext) {
+        let screen = self.view.screen;
+        let viewport = self.view.curve;
+        let (step_x, step_y) = self.tick_steps();
+        let mut label = |value: f64, step: f64| {
+            // Avoid labelling zero as -0.0
+            let value = if value.abs() < step * 1e-6 { 0. } else { value };
+            ctx.text()
+                .new_text_layout(format!("{:.*}", ticks::decimals(step), value))
+                .font(FontFamily::MONOSPACE, 10.0)
+                .text_color(Color::BLACK)
+                .build()
+                .unwrap()
+        };
+        let x_labels: Vec<_> = ticks::ticks(viewport.x0, viewport.x1, step_x)
+            .into_iter()
+            .map(|x| (self.screen_x(x), label(x, step_x)))
+            .collect();
+        let y_labels: Vec<_> = ticks::ticks(viewport.y0, viewport.y1, step_y)
+            .into_iter()
+            .map(|y| (self.screen_y(y), label(y, step_y)))
+            .collect();
+
+        for (x, layout) in x_labels {
+            ctx.stroke(
+                Line::new((x, screen.y1), (x, screen.y1 + 4.)),
+                &Color::BLACK,
+                1.,
+            );
+            ctx.draw_text(&layout, (x - layout.size().width / 2., screen.y1 + 4.));
+        }
+        for (y, layout) in y_labels {
+            ctx.stroke(
+                Line::new((screen.x0 - 4., y), (screen.x0, y)),
+                &Color::BLACK,
+                1.,
+            );
+            ctx.draw_text(
+                &layout,
+                (
+                    screen.x0 - layout.size().width - 6.,
+                    y - layout.size().height / 2.,
+                ),
+            );
+        }
+    }
+
+    // Screen positions of the scale and rotation handles around a bounding box
+    fn gizmo_handles(&self, bounds: [f32; 4]) -> Vec<(GizmoHandle, Point)> {
+        let centre = Vec2::new((bounds[0] + bounds[2]) / 2., (bounds[1] + bounds[3]) / 2.);
+        let half = Vec2::new((bounds[2] - bounds[0]) / 2., (bounds[3] - bounds[1]) / 2.);
+
+        let mut handles = Vec::new();
+        for side_x in -1..=1 {
+            for side_y in -1..=1 {
+                if side_x != 0 || side_y != 0 {
+                    let p = Vec2::new(
+                        centre.x + side_x as f32 * half.x,
+                        centre.y + side_y as f32 * half.y,
+                    );
+                    handles.push((
+                        GizmoHandle::Scale(side_x, side_y),
+                        self.map_curvespace_to_screenspace(&p),
+                    ));
+                }
+            }
+        }
+
+        let top = self.map_curvespace_to_screenspace(&Vec2::new(centre.x, bounds[3]));
+        handles.push((
+            GizmoHandle::Rotate,
+            Point::new(top.x, top.y - ROTATE_HANDLE_OFFSET),
+        ));
+        handles
+    }
+
+    fn pick_gizmo_handle(&self, bounds: [f32; 4], pos: Point) -> Option<GizmoHandle> {
+        let handle = self
+            .gizmo_handles(bounds)
+            .into_iter()
+            .find(|(_, p)| p.distance(pos) < 8.)
+            .map(|(h, _)| h);
+        if handle.is_some() {
+            return handle;
+        }
+
+        let box_rect = Rect::from_points(
+            self.map_curvespace_to_screenspace(&Vec2::new(bounds[0], bounds[1])),
+            self.map_curvespace_to_screenspace(&Vec2::new(bounds[2], bounds[3])),
+        );
+        if box_rect.contains(pos) {
+            Some(GizmoHandle::Move)
+        } else {
+            None
+        }
+    }
+
+    // Transform the spline as it was when the drag began to follow the mouse
+    fn drag_gizmo(&self, data: &mut AppData, pos: Point) {
+        let drag = match &self.gizmo_drag {
+            Some(drag) => drag,
+            None => return,
+        };
+        let b = drag.bounds;
+        let centre = Vec2::new((b[0] + b[2]) / 2., (b[1] + b[3]) / 2.);
+        let p = self.map_screenspace_to_curvespace(pos);
+        let identity = Matrix2x2::identity();
+
+        data.spline = match drag.handle {
+            GizmoHandle::Move => drag.original.transform(identity, p - drag.start),
+            GizmoHandle::Rotate => {
+                let theta = (drag.start - centre).angle_between(p - centre);
+                drag.original
+                    .transform_about(identity.rotation(theta), centre, Vec2::new(0., 0.))
+            }
+            GizmoHandle::Scale(side_x, side_y) => {
+                // Scale about the opposite side so it stays put
+                let anchor = Vec2::new(
+                    centre.x - side_x as f32 * (b[2] - b[0]) / 2.,
+                    centre.y - side_y as f32 * (b[3] - b[1]) / 2.,
+                );
+                let factor = |moved: f32, anchor: f32, start: f32| {
+                    if (start - anchor).abs() < 1e-9 {
+                        1.
+                    } else {
+                        (moved - anchor) / (start - anchor)
+                    }
+                };
+                let scale_x = if side_x != 0 {
+                    factor(p.x, anchor.x, drag.start.x)
+                } else {
+                    1.
+                };
+                let scale_y = if side_y != 0 {
+                    factor(p.y, anchor.y, drag.start.y)
+                } else {
+                    1.
+                };
+                drag.original.transform_about(
+                    identity.scale_xy(scale_x, scale_y),
+                    anchor,
+                    Vec2::new(0., 0.),
+                )
+            }
+        };
+    }
+
+    pub fn render_gizmo(&self, ctx: &mut impl RenderContext, data: &AppData) {
+        let bounds = match data.spline.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        let box_rect = Rect::from_points(
+            self.map_curvespace_to_screenspace(&Vec2::new(bounds[0], bounds[1])),
+            self.map_curvespace_to_screenspace(&Vec2::new(bounds[2], bounds[3])),
+        );
+        let dashed = StrokeStyle::new().dash_pattern(&[4., 3.]);
+        ctx.stroke_styled(box_rect, &Color::NAVY, 1., &dashed);
+
+        let top = Point::new(box_rect.center().x, box_rect.y0);
+        for (handle, p) in self.gizmo_handles(bounds) {
+            if handle == GizmoHandle::Rotate {
+                ctx.stroke(Line::new(top, p), &Color::NAVY, 1.);
+                ctx.fill(Circle::new(p, 4.), &Color::WHITE);
+                ctx.stroke(Circle::new(p, 4.), &Color::NAVY, 1.);
+            } else {
+                let square = Rect::from_center_size(p, Size::new(7., 7.));
+                ctx.fill(square, &Color::WHITE);
+                ctx.stroke(square, &Color::NAVY, 1.);
+            }
+        }
+    }
 }
 
 impl Widget<AppData> for ContainerWidget {
@@ -159,44 +966,102 @@ impl Widget<AppData> for ContainerWidget {
         let mut repaint: bool = false;
 
         if let Event::MouseDown(m) = event {
-            if m.button.is_left() {
+            // Take keyboard focus so overlay shortcuts work
+            ctx.request_focus();
+
+            if m.button.is_middle() || (m.button.is_left() && self.space_down) {
+                // Pan with the middle button, or the left while space is held
+                self.pan = Some((m.pos, data.viewport));
+            } else if m.button.is_left() && data.boolean_op.is_some() {
+                // Pick boolean operands instead of dragging
+                if let Some(i) = self.pick_shape(data, m.pos) {
+                    if let Some(j) = self.boolean_operands.iter().position(|&k| k == i) {
+                        self.boolean_operands.remove(j);
+                    } else {
+                        if self.boolean_operands.len() == 2 {
+                            self.boolean_operands.remove(0);
+                        }
+                        self.boolean_operands.push(i);
+                    }
+                }
+                repaint = true;
+            } else if m.button.is_left() && data.transform_mode {
+                // Grab a gizmo handle, or move the whole spline from inside its box
+                if let Some(bounds) = data.spline.bounds() {
+                    if let Some(handle) = self.pick_gizmo_handle(bounds, m.pos) {
+                        self.gizmo_drag = Some(GizmoDrag {
+                            handle,
+                            start: self.map_screenspace_to_curvespace(m.pos),
+                            original: data.spline.clone(),
+                            bounds,
+                        });
+                    }
+                }
+            } else if m.button.is_left()
+                && m.count == 2
+                && self.pick_handle(data, m.pos).is_none()
+                && self.insert_point(data, m.pos)
+            {
+                // Double clicking on the curve adds a node there
+                self.hover_handle = None;
+                repaint = true;
+            } else if m.button.is_left() {
                 self.dragging = true;
                 self.drag_start = m.pos;
                 self.drag_pos = m.pos;
 
-                // Retrieve the first curve's control points and
-                // convert them to screen space
-                let control_points = data.spline[0]
-                    .control_points
-                    .iter()
-                    .map(|p| self.map_curvespace_to_screenspace(p));
-
-                // Check if the mouse is near a control point
-                // If it is, set that as the active point for the drag
-                // TODO: this is hacky, use a z-stack?
-                for p in control_points.enumerate() {
-                    if p.1.distance(self.drag_pos) < 10. {
-                        self.drag_object = Some(p.0);
-                        break;
-                    }
-                }
+                // Grab the nearest control handle of any segment, selecting it. Clicking
+                // away from every handle clears the selection.
+                self.drag_object = self.pick_handle(data, self.drag_pos);
+                data.selection = self.drag_object;
+                self.drag_original = self.drag_object.map(|_| data.spline.clone());
 
                 repaint = true;
             }
         } else if let Event::MouseMove(m) = event {
-            if self.dragging {
+            if self.pan.is_some() {
+                self.drag_pan(data, m.pos);
+                repaint = true;
+            } else if self.gizmo_drag.is_some() {
+                self.drag_gizmo(data, m.pos);
+                repaint = true;
+            } else if self.dragging {
                 self.drag_pos = Point::new(m.pos.x, m.pos.y);
-                self.drag_point(&mut data.spline[0]); // TODO: Handle multiple draggable curves
+                // Alt suspends snapping
+                self.drag_point(data, !m.mods.alt());
 
                 repaint = true;
+            } else {
+                self.hover_pos = Some(m.pos);
+                let hover_handle = self.pick_handle(data, m.pos);
+                repaint = data.curvature_overlay
+                    || data.show_inspector
+                    || hover_handle != self.hover_handle;
+                self.hover_handle = hover_handle;
             }
         } else if let Event::MouseUp(m) = event {
-            if m.button.is_left() {
+            if self.pan.is_some() {
+                self.drag_pan(data, m.pos);
+                self.pan = None;
+                repaint = true;
+            } else if m.button.is_left() && self.gizmo_drag.is_some() {
+                self.drag_gizmo(data, m.pos);
+                if let Some(drag) = self.gizmo_drag.take() {
+                    data.record("Transform", drag.original);
+                }
+                repaint = true;
+            } else if m.button.is_left() {
                 // Update drag position in case mouse movement is captured here
                 self.drag_pos = Point::new(m.pos.x, m.pos.y);
-                self.drag_point(&mut data.spline[0]);
+                self.drag_point(data, !m.mods.alt());
+
+                // The whole drag undoes as one command
+                if let Some(before) = self.drag_original.take() {
+                    data.record("Move point", before);
+                }
 
                 // Clear drag event
+                self.snap = None;
                 self.dragging = false;
                 self.drag_start = Point::ZERO;
                 self.drag_pos = Point::ZERO;
@@ -204,6 +1069,104 @@ impl Widget<AppData> for ContainerWidget {
 
                 repaint = true;
             }
+        } else if let Event::Wheel(m) = event {
+            // Zoom about the point under the cursor
+            let about = self.map_screenspace_to_curvespace(m.pos);
+            data.zoom(about, 1.0015_f64.powf(m.wheel_delta.y));
+            repaint = true;
+        } else if let Event::KeyUp(k) = event {
+            if k.key == KbKey::Character(" ".into()) {
+                self.space_down = false;
+            }
+        } else if let Event::KeyDown(k) = event {
+            // Overlay shortcuts
+            repaint = true;
+            match &k.key {
+                KbKey::Character(c) if c == " " => {
+                    self.space_down = true;
+                    repaint = false;
+                }
+                KbKey::Character(c) if c == "g" => data.show_grid = !data.show_grid,
+                KbKey::Character(c) if c == "m" => data.show_minor_grid = !data.show_minor_grid,
+                KbKey::Character(c) if c == "l" => data.show_tick_labels = !data.show_tick_labels,
+                KbKey::Character(c) if c == "x" => data.show_origin = !data.show_origin,
+                KbKey::Character(c) if c == "e" => data.equal_aspect = !data.equal_aspect,
+                KbKey::Character(c) if c == "1" => data.snap.grid = !data.snap.grid,
+                KbKey::Character(c) if c == "2" => data.snap.points = !data.snap.points,
+                KbKey::Character(c) if c == "3" => data.snap.tangent = !data.snap.tangent,
+                KbKey::Character(c) if c == "4" => data.snap.angle = !data.snap.angle,
+                KbKey::Character(c) if c == "f" => data.zoom_to_fit(),
+                KbKey::Character(c) if c == "F" => data.zoom_to_selection(),
+                KbKey::Character(c) if c == "k" => data.curvature_overlay = !data.curvature_overlay,
+                KbKey::Character(c) if c == "[" => {
+                    data.set_parameter(Parameter::CombScale, data.comb_scale / 1.25)
+                }
+                KbKey::Character(c) if c == "]" => {
+                    data.set_parameter(Parameter::CombScale, data.comb_scale * 1.25)
+                }
+                KbKey::Character(c) if c == "," => data.set_parameter(
+                    Parameter::CombDensity,
+                    (data.comb_density / 2).max(4) as f64,
+                ),
+                KbKey::Character(c) if c == "." => data.set_parameter(
+                    Parameter::CombDensity,
+                    (data.comb_density * 2).min(1024) as f64,
+                ),
+                KbKey::Character(c) if c == "i" => data.show_info = !data.show_info,
+                KbKey::Character(c) if c == "h" => data.show_inspector = !data.show_inspector,
+                KbKey::Character(c) if c == "t" => data.transform_mode = !data.transform_mode,
+                KbKey::Character(c) if c == "c" => data.control_style = data.control_style.next(),
+                KbKey::Escape => data.selection = None,
+                KbKey::Character(c) if c == "a" => {
+                    // Extend the path to the mouse
+                    if let Some(pos) = self.hover_pos {
+                        let p = self.map_screenspace_to_curvespace(pos);
+                        let before = data.spline.clone();
+                        data.spline.append_segment(p);
+                        data.record("Append segment", before);
+                        let end = match data.control_style {
+                            Basis::Hermite => 1,
+                            _ => 3,
+                        };
+                        data.selection = Some((data.spline.len() - 1, end));
+                    }
+                }
+                KbKey::Delete | KbKey::Backspace => {
+                    if let Some(k) = ContainerWidget::selected_node(data) {
+                        let before = data.spline.clone();
+                        if data.spline.delete_node(k) {
+                            data.record("Delete point", before);
+                            data.selection = None;
+                            self.hover_handle = None;
+                        }
+                    }
+                }
+                KbKey::Character(c) if c == "o" => {
+                    let before = data.spline.clone();
+                    if data.spline.closed {
+                        data.spline.open();
+                        data.record("Open path", before);
+                    } else {
+                        data.spline.close();
+                        data.record("Close path", before);
+                    }
+                    data.selection = None;
+                }
+                KbKey::Character(c) if c == "b" => {
+                    // Cycle through the boolean operations, then back to editing
+                    data.boolean_op = match data.boolean_op {
+                        None => Some(BooleanOp::Union),
+                        Some(BooleanOp::Union) => Some(BooleanOp::Intersection),
+                        Some(BooleanOp::Intersection) => Some(BooleanOp::Difference),
+                        Some(BooleanOp::Difference) => Some(BooleanOp::Xor),
+                        Some(BooleanOp::Xor) => None,
+                    };
+                    if data.boolean_op.is_none() {
+                        self.boolean_operands.clear();
+                    }
+                }
+                _ => repaint = false,
+            }
         }
 
         // Request a repaint if something happened
@@ -212,16 +1175,29 @@ impl Widget<AppData> for ContainerWidget {
         }
     }
 
-    fn lifecycle(
-        &mut self,
-        _ctx: &mut LifeCycleCtx,
-        _event: &LifeCycle,
-        _data: &AppData,
-        _env: &Env,
-    ) {
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &AppData, _env: &Env) {
+        match event {
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(false) => {
+                // Drop the hover overlay when the mouse leaves the canvas
+                self.hover_pos = None;
+                if data.curvature_overlay
+                    || data.show_inspector
+                    || self.hover_handle.take().is_some()
+                {
+                    ctx.request_paint();
+                }
+            }
+            _ => {}
+        }
     }
 
-    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &AppData, _data: &AppData, _env: &Env) {}
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &AppData, data: &AppData, _env: &Env) {
+        // The side panel can change the spline and display options
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
 
     fn layout(
         &mut self,
@@ -273,8 +1249,9 @@ impl Widget<AppData> for ContainerWidget {
             .build()
             .unwrap();
 
-        // Create plot area based on axis limit label widths
-        let canvas_viewport_screen = Rect::from_center_size(
+        // Create plot area based on axis limit label widths, letterboxed to the viewport's
+        // aspect ratio if required
+        let plot_area = Rect::from_center_size(
             canvas_rect.center(),
             canvas_rect
                 .inset(
@@ -286,54 +1263,152 @@ impl Widget<AppData> for ContainerWidget {
                 .size(),
         );
 
-        // Cache viewports
-        self.canvas_rect = canvas_rect;
-        self.canvas_viewport_screen = canvas_viewport_screen;
-        self.canvas_viewport_curve = data.viewport;
+        let view = ViewTransform::new(plot_area, data.viewport, data.equal_aspect);
+        let canvas_viewport_screen = view.screen;
+
+        // Cache the view
+        self.view = view;
+
+        // Draw the grid beneath everything else in the plot area
+        ctx.with_save(|ctx| {
+            ctx.clip(canvas_viewport_screen);
+            self.render_grid(ctx.render_ctx, data);
+        });
 
         // Draw outline of plot area
         ctx.stroke(canvas_viewport_screen, &Color::BLACK, 1.);
 
-        // Draw axis limit labels
-        ctx.draw_text(
-            &x_min_label,
-            (canvas_viewport_screen.x0, canvas_viewport_screen.y1),
-        );
-        ctx.draw_text(
-            &x_max_label,
-            (
-                canvas_viewport_screen.x1 - x_max_label.image_bounds().width(),
-                canvas_viewport_screen.y1,
-            ),
-        );
-        ctx.draw_text(
-            &y_min_label,
-            (
-                canvas_viewport_screen.x0 - y_min_label.image_bounds().width() - 4.,
-                canvas_viewport_screen.y1 - y_min_label.image_bounds().height() - 4.,
-            ),
-        );
-        ctx.draw_text(
-            &y_max_label,
-            (
-                canvas_viewport_screen.x0 - y_max_label.image_bounds().width() - 4.,
-                canvas_viewport_screen.y0,
-            ),
-        );
+        // Label the ticks, or failing that the axis limits
+        if data.show_tick_labels {
+            self.render_tick_labels(ctx.render_ctx);
+        } else {
+            ctx.draw_text(
+                &x_min_label,
+                (canvas_viewport_screen.x0, canvas_viewport_screen.y1),
+            );
+            ctx.draw_text(
+                &x_max_label,
+                (
+                    canvas_viewport_screen.x1 - x_max_label.image_bounds().width(),
+                    canvas_viewport_screen.y1,
+                ),
+            );
+            ctx.draw_text(
+                &y_min_label,
+                (
+                    canvas_viewport_screen.x0 - y_min_label.image_bounds().width() - 4.,
+                    canvas_viewport_screen.y1 - y_min_label.image_bounds().height() - 4.,
+                ),
+            );
+            ctx.draw_text(
+                &y_max_label,
+                (
+                    canvas_viewport_screen.x0 - y_max_label.image_bounds().width() - 4.,
+                    canvas_viewport_screen.y0,
+                ),
+            );
+        }
+
+        let shapes = ContainerWidget::shapes(data);
+
+        // Fill closed paths beneath their outlines
+        if data.spline.closed {
+            self.render_fill(ctx.render_ctx, &data.spline, &Color::rgba8(0, 128, 128, 40));
+        }
 
-        let mut curves = data.spline.clone();
+        // Plot the source curves with their controls, then the offset curves
+        for (i, (shape, color)) in shapes.iter().enumerate() {
+            for curve in &shape.segments {
+                self.render_curve(ctx.render_ctx, data, curve, color, i == 0);
+            }
+        }
+        self.render_legend(ctx.render_ctx, data);
+
+        // Preview the boolean operation on the chosen shapes
+        if let Some(op) = data.boolean_op {
+            self.render_boolean_preview(ctx.render_ctx, &shapes, op);
 
-        // Add offset curves
-        curves.push(curves[0].offset_levien(data.offset));
-        curves.push(curves[0].offset_tiller(-data.offset));
-        curves.push(curves[0].offset_klass(-2. * data.offset));
+            let label = ctx
+                .text()
+                .new_text_layout(format!(
+                    "Boolean {:?}: pick two shapes ({}/2)",
+                    op,
+                    self.boolean_operands.len()
+                ))
+                .font(FontFamily::MONOSPACE, 10.0)
+                .text_color(Color::NAVY)
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &label,
+                (
+                    canvas_viewport_screen.x0 + 4.,
+                    canvas_viewport_screen.y1 - label.size().height - 4.,
+                ),
+            );
+        }
 
-        // Plot curves
-        for curve in curves.iter().enumerate() {
-            match curve.0 {
-                0 => self.render_curve(ctx.render_ctx, data, curve.1, true),
-                _ => self.render_curve(ctx.render_ctx, data, curve.1, false),
+        // Draw the curvature overlay
+        if data.curvature_overlay {
+            for curve in &data.spline.segments {
+                self.render_curvature_comb(ctx.render_ctx, data, curve);
             }
+            self.render_osculating_circle(ctx.render_ctx, data);
         }
+
+        // Highlight the hovered and selected control handles
+        self.render_handle_highlights(ctx.render_ctx, data);
+        self.render_snap_indicator(ctx.render_ctx, data);
+
+        // Describe the curve under the mouse, unless something is being dragged
+        if data.show_inspector && !self.dragging && self.gizmo_drag.is_none() {
+            self.render_inspector(ctx.render_ctx, data, &shapes);
+        }
+
+        // Draw the transform gizmo around the spline
+        if data.transform_mode {
+            self.render_gizmo(ctx.render_ctx, data);
+        }
+
+        // Draw the area and moment readout
+        if data.show_info {
+            self.render_info_panel(ctx.render_ctx, data);
+        }
+
+        // Mark and label the geometric features of each source curve
+        for (i, curve) in data.spline.segments.iter().enumerate() {
+            self.render_analysis(ctx.render_ctx, curve);
+
+            let label = ctx
+                .text()
+                .new_text_layout(format!("Segment {}: {:?}", i, curve.classify()))
+                .font(FontFamily::MONOSPACE, 10.0)
+                .text_color(Color::BLACK)
+                .build()
+                .unwrap();
+            ctx.draw_text(
+                &label,
+                (
+                    canvas_viewport_screen.x0 + 4.,
+                    canvas_viewport_screen.y0 + 4. + 14. * i as f64,
+                ),
+            );
+        }
+
+        // Name the control point representation below the segment list
+        let label = ctx
+            .text()
+            .new_text_layout(format!("Controls: {:?}", data.control_style))
+            .font(FontFamily::MONOSPACE, 10.0)
+            .text_color(Color::GRAY)
+            .build()
+            .unwrap();
+        ctx.draw_text(
+            &label,
+            (
+                canvas_viewport_screen.x0 + 4.,
+                canvas_viewport_screen.y0 + 4. + 14. * data.spline.len() as f64,
+            ),
+        );
     }
 }
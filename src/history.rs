@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+
+use crate::math::path::Path;
+
+// Most commands kept for undo; older ones are dropped
+const HISTORY_LIMIT: usize = 200;
+
+// Numeric display and analysis parameters whose changes can be undone
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum Parameter {
+    Offset,
+    CombScale,
+    CombDensity,
+}
+
+// A reversible edit, holding the state it replaced and the state it produced
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum Command {
+    // Any change to the spline: point drags, segment edits and transforms
+    Spline {
+        name: String,
+        before: Path,
+        after: Path,
+    },
+    Parameter {
+        parameter: Parameter,
+        before: f64,
+        after: f64,
+    },
+}
+
+impl Command {
+    pub fn name(&self) -> String {
+        match self {
+            Command::Spline { name, .. } => name.clone(),
+            Command::Parameter { parameter, .. } => format!("{:?}", parameter),
+        }
+    }
+
+    // Fold a following command into this one if both change the same parameter, or are
+    // spline edits of the same name, so a run of adjustments undoes in one step
+    fn coalesce(&mut self, next: &Command) -> bool {
+        match (self, next) {
+            (
+                Command::Parameter {
+                    parameter, after, ..
+                },
+                Command::Parameter {
+                    parameter: next_parameter,
+                    after: next_after,
+                    ..
+                },
+            ) if parameter == next_parameter => {
+                *after = *next_after;
+                true
+            }
+            (
+                Command::Spline { name, after, .. },
+                Command::Spline {
+                    name: next_name,
+                    after: next_after,
+                    ..
+                },
+            ) if name == next_name => {
+                *after = next_after.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// Undo and redo stacks of commands, most recent last
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<Command>,
+    redo: Vec<Command>,
+    // Whether the last command belongs to an interaction still in progress, such as a
+    // slider drag, so that further changes from it fold into the same step
+    #[serde(skip)]
+    open: bool,
+}
+
+impl History {
+    // Record a command that has just been carried out, discarding anything to redo
+    pub fn push(&mut self, command: Command) {
+        self.open = false;
+        self.redo.clear();
+        self.undo.push(command);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    // Record a command from an interaction in progress. It is folded into the previous
+    // command if that came from the same interaction and made the same kind of change.
+    pub fn push_merged(&mut self, command: Command) {
+        if self.open {
+            if let Some(last) = self.undo.last_mut() {
+                if last.coalesce(&command) {
+                    self.redo.clear();
+                    return;
+                }
+            }
+        }
+        self.push(command);
+        self.open = true;
+    }
+
+    // End the interaction in progress, so its next change starts a new step
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    // The command to revert, which moves onto the redo stack
+    pub fn undo(&mut self) -> Option<Command> {
+        self.open = false;
+        let command = self.undo.pop()?;
+        self.redo.push(command.clone());
+        Some(command)
+    }
+
+    // The command to carry out again, which moves back onto the undo stack
+    pub fn redo(&mut self) -> Option<Command> {
+        self.open = false;
+        let command = self.redo.pop()?;
+        self.undo.push(command.clone());
+        Some(command)
+    }
+
+    pub fn undo_name(&self) -> Option<String> {
+        self.undo.last().map(Command::name)
+    }
+
+    pub fn redo_name(&self) -> Option<String> {
+        self.redo.last().map(Command::name)
+    }
+
+    // The spline the most recent spline command left behind, to check a saved history
+    // still matches the saved spline
+    pub fn last_spline(&self) -> Option<&Path> {
+        self.undo.iter().rev().find_map(|command| match command {
+            Command::Spline { after, .. } => Some(after),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use crate::history::{Command, History, Parameter, HISTORY_LIMIT};
+    use crate::math::bezier3::Bezier3;
+    use crate::math::path::Path;
+    use crate::math::vec2::Vec2;
+
+    // A one segment path lying along y = 0 from x to x + 1
+    fn path(x: f32) -> Path {
+        let points = (0..4).map(|i| Vec2::new(x + i as f32 / 3., 0.)).collect();
+        Path::new(vec![Bezier3::new(points)], false)
+    }
+
+    fn edit(name: &str, before: f32, after: f32) -> Command {
+        Command::Spline {
+            name: name.to_string(),
+            before: path(before),
+            after: path(after),
+        }
+    }
+
+    fn offset(before: f64, after: f64) -> Command {
+        Command::Parameter {
+            parameter: Parameter::Offset,
+            before,
+            after,
+        }
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut history = History::default();
+        assert_eq!(history.undo(), None);
+        history.push(edit("Move point", 0., 1.));
+        history.push(edit("Flip", 1., 2.));
+        assert_eq!(history.undo_name().as_deref(), Some("Flip"));
+
+        assert_eq!(history.undo(), Some(edit("Flip", 1., 2.)));
+        assert_eq!(history.redo_name().as_deref(), Some("Flip"));
+        assert_eq!(history.redo(), Some(edit("Flip", 1., 2.)));
+        assert_eq!(history.redo(), None);
+
+        // A new command abandons whatever could have been redone
+        history.undo();
+        history.push(edit("Transform", 1., 3.));
+        assert_eq!(history.redo_name(), None);
+        assert_eq!(history.undo(), Some(edit("Transform", 1., 3.)));
+        assert_eq!(history.undo(), Some(edit("Move point", 0., 1.)));
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn limit() {
+        let mut history = History::default();
+        for i in 0..HISTORY_LIMIT + 5 {
+            history.push(offset(i as f64, i as f64 + 1.));
+        }
+        let mut steps = 0;
+        while history.undo().is_some() {
+            steps += 1;
+        }
+        assert_eq!(steps, HISTORY_LIMIT);
+
+        // The oldest commands were the ones dropped
+        assert_eq!(history.redo(), Some(offset(5., 6.)));
+    }
+
+    #[test]
+    fn merging() {
+        let mut history = History::default();
+
+        // Changes within one interaction merge, keeping the first before
+        history.push_merged(offset(0.1, 0.2));
+        history.push_merged(offset(0.2, 0.3));
+        history.push_merged(edit("Edit P1", 0., 1.));
+        history.push_merged(edit("Edit P1", 1., 2.));
+        assert_eq!(history.undo(), Some(edit("Edit P1", 0., 2.)));
+        assert_eq!(history.undo(), Some(offset(0.1, 0.3)));
+
+        // Closing the interaction, or a plain push, starts a new step
+        let mut history = History::default();
+        history.push_merged(offset(0.1, 0.2));
+        history.close();
+        history.push_merged(offset(0.2, 0.3));
+        history.push(offset(0.3, 0.4));
+        history.push_merged(offset(0.4, 0.5));
+        assert_eq!(history.undo(), Some(offset(0.4, 0.5)));
+        assert_eq!(history.undo(), Some(offset(0.3, 0.4)));
+        assert_eq!(history.undo(), Some(offset(0.2, 0.3)));
+
+        // Undoing ends the interaction too
+        history.push_merged(offset(0.2, 0.6));
+        assert_eq!(history.undo(), Some(offset(0.2, 0.6)));
+        assert!(!history.is_open());
+    }
+
+    #[test]
+    fn last_spline() {
+        let mut history = History::default();
+        assert_eq!(history.last_spline(), None);
+        history.push(edit("Move point", 0., 1.));
+        history.push(offset(0.1, 0.2));
+        assert_eq!(history.last_spline(), Some(&path(1.)));
+        history.undo();
+        history.undo();
+        assert_eq!(history.last_spline(), None);
+    }
+}
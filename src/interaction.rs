@@ -0,0 +1,43 @@
+use druid::widget::Controller;
+use druid::{Env, Event, EventCtx, LifeCycle, LifeCycleCtx, Widget};
+
+use crate::app_data::AppData;
+
+// Wraps a control whose changes merge into one undo step while it is in use, such as a
+// slider or a text box. Pressing the control or moving focus into it starts a new step.
+#[derive(Default)]
+pub struct Interaction {
+    // Focus arrived since the last event, which ends the previous interaction
+    focus_gained: bool,
+}
+
+impl<W: Widget<AppData>> Controller<AppData, W> for Interaction {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AppData,
+        env: &Env,
+    ) {
+        if self.focus_gained || matches!(event, Event::MouseDown(_)) {
+            self.focus_gained = false;
+            data.end_interaction();
+        }
+        child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &AppData,
+        env: &Env,
+    ) {
+        if let LifeCycle::FocusChanged(true) = event {
+            self.focus_gained = true;
+        }
+        child.lifecycle(ctx, event, data, env)
+    }
+}
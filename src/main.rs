@@ -1,3 +1,4 @@
+use druid::widget::{CrossAxisAlignment, Flex};
 use druid::{AppLauncher, Rect, WindowDesc};
 use preferences::AppInfo;
 mod app_data;
@@ -5,7 +6,16 @@ use app_data::*;
 mod container_widget;
 use container_widget::*;
 mod app_delegate;
+mod history;
+mod interaction;
 mod math;
+mod menu;
+mod number_format;
+mod offset_panel;
+mod point_table;
+mod snapping;
+mod transform_panel;
+mod view_transform;
 use app_delegate::Delegate;
 
 use math::{bezier3::Bezier3, vec2::Vec2};
@@ -18,14 +28,20 @@ pub const APP_SIG: AppInfo = AppInfo {
 fn main() {
     let mut data = AppData::new();
 
-    // Set the curve offset distance
-    data.offset = 0.075;
-
     // Set the curvespace viewport
     data.viewport = Rect::new(0., 0., 1., 1.);
 
     // Launch the program
-    let window = WindowDesc::new(ContainerWidget::new());
+    let root = Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(ContainerWidget::new(), 1.)
+        .with_child(
+            Flex::column()
+                .with_child(offset_panel::build())
+                .with_child(transform_panel::build())
+                .with_flex_child(point_table::build(), 1.),
+        );
+    let window = WindowDesc::new(root).menu(menu::build);
     AppLauncher::with_window(window)
         .delegate(Delegate::new())
         .log_to_console()
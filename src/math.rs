@@ -1,18 +1,35 @@
 // Solvers
 pub mod constants;
+pub mod polynomial;
 pub mod solvers;
 
 // Scalars
-// pub mod integer;
+pub mod dual;
+pub mod integer;
+pub mod interval;
+pub mod rational;
 pub mod real;
+pub mod ticks;
 
 // Vectors and matrices
 pub mod matrix2x2;
 pub mod matrix3x3;
+pub mod matrix4x4;
 pub mod vec2;
 // pub mod vec3;
 
 // Curves
+pub mod basis;
 pub mod bezier3;
 // pub mod bezier4;
 // pub mod hermite3;
+
+// Geometry
+pub mod boolean;
+pub mod intersection;
+pub mod moments;
+pub mod path;
+pub mod predicates;
+
+#[cfg(test)]
+pub mod fixtures;
@@ -0,0 +1,199 @@
+use super::{matrix4x4::Matrix4x4, vec2::Vec2};
+
+// Cubic curve representations. Each writes the curve as [t^3 t^2 t 1] * M * G for its
+// basis matrix M and four control values G:
+// - Bezier: the four control points
+// - Hermite: the two endpoints followed by the tangents at them
+// - BSpline: four uniform B-spline control points, the curve staying inside their hull
+// - CatmullRom: four points, the curve running from the second to the third
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Basis {
+    Bezier,
+    Hermite,
+    BSpline,
+    CatmullRom,
+}
+
+// Inverses of the basis matrices, written out so conversions don't invert them every call
+const BEZIER_INVERSE: Matrix4x4<f32> = Matrix4x4 {
+    m: [
+        [0., 0., 0., 1.],
+        [0., 0., 1. / 3., 1.],
+        [0., 1. / 3., 2. / 3., 1.],
+        [1., 1., 1., 1.],
+    ],
+};
+const HERMITE_INVERSE: Matrix4x4<f32> = Matrix4x4 {
+    m: [
+        [0., 0., 0., 1.],
+        [1., 1., 1., 1.],
+        [0., 0., 1., 0.],
+        [3., 2., 1., 0.],
+    ],
+};
+const B_SPLINE_INVERSE: Matrix4x4<f32> = Matrix4x4 {
+    m: [
+        [0., 2. / 3., -1., 1.],
+        [0., -1. / 3., 0., 1.],
+        [0., 2. / 3., 1., 1.],
+        [6., 11. / 3., 2., 1.],
+    ],
+};
+const CATMULL_ROM_INVERSE: Matrix4x4<f32> = Matrix4x4 {
+    m: [
+        [1., 1., -1., 1.],
+        [0., 0., 0., 1.],
+        [1., 1., 1., 1.],
+        [6., 4., 2., 1.],
+    ],
+};
+
+impl Basis {
+    pub fn matrix(&self) -> Matrix4x4<f32> {
+        match self {
+            Basis::Bezier => Matrix4x4::new([
+                [-1., 3., -3., 1.],
+                [3., -6., 3., 0.],
+                [-3., 3., 0., 0.],
+                [1., 0., 0., 0.],
+            ]),
+            Basis::Hermite => Matrix4x4::new([
+                [2., -2., 1., 1.],
+                [-3., 3., -2., -1.],
+                [0., 0., 1., 0.],
+                [1., 0., 0., 0.],
+            ]),
+            Basis::BSpline => Matrix4x4::new([
+                [-1., 3., -3., 1.],
+                [3., -6., 3., 0.],
+                [-3., 0., 3., 0.],
+                [1., 4., 1., 0.],
+            ])
+            .scale(1. / 6.),
+            Basis::CatmullRom => Matrix4x4::new([
+                [-1., 3., -3., 1.],
+                [2., -5., 4., -1.],
+                [-1., 0., 1., 0.],
+                [0., 2., 0., 0.],
+            ])
+            .scale(0.5),
+        }
+    }
+
+    pub fn inverse_matrix(&self) -> Matrix4x4<f32> {
+        match self {
+            Basis::Bezier => BEZIER_INVERSE,
+            Basis::Hermite => HERMITE_INVERSE,
+            Basis::BSpline => B_SPLINE_INVERSE,
+            Basis::CatmullRom => CATMULL_ROM_INVERSE,
+        }
+    }
+
+    pub fn next(&self) -> Basis {
+        match self {
+            Basis::Bezier => Basis::Hermite,
+            Basis::Hermite => Basis::BSpline,
+            Basis::BSpline => Basis::CatmullRom,
+            Basis::CatmullRom => Basis::Bezier,
+        }
+    }
+}
+
+// Re-express the control values of a curve in another basis, G_to = M_to^-1 * M_from * G_from
+pub fn convert(g: &[Vec2<f32>; 4], from: Basis, to: Basis) -> [Vec2<f32>; 4] {
+    if from == to {
+        return *g;
+    }
+    let m = to.inverse_matrix() * from.matrix();
+    m.apply(g)
+}
+
+#[cfg(test)]
+mod basis_tests {
+    use crate::math::basis::{convert, Basis};
+    use crate::math::bezier3::Bezier3;
+    use crate::math::vec2::Vec2;
+
+    #[test]
+    fn round_trips() {
+        let bezier = [
+            Vec2::new(0.2, 0.2),
+            Vec2::new(0.1, 0.9),
+            Vec2::new(0.4, 0.4),
+            Vec2::new(0.9, 0.8),
+        ];
+
+        // Hermite tangents are three times the Bezier handles
+        let hermite = convert(&bezier, Basis::Bezier, Basis::Hermite);
+        assert!(hermite[1].approx_eq(bezier[3], 1e-6));
+        assert!(hermite[2].approx_eq((bezier[1] - bezier[0]) * 3., 1e-5));
+
+        for basis in [Basis::Hermite, Basis::BSpline, Basis::CatmullRom] {
+            let g = convert(&bezier, Basis::Bezier, basis);
+            let back = convert(&g, basis, Basis::Bezier);
+            for (p, q) in back.iter().zip(bezier.iter()) {
+                assert!(p.approx_eq(*q, 1e-4));
+            }
+        }
+
+        // A B-spline starts at (P0 + 4 P1 + P2) / 6
+        let b_spline = [
+            Vec2::new(0., 0.),
+            Vec2::new(6., 0.),
+            Vec2::new(6., 6.),
+            Vec2::new(0., 6.),
+        ];
+        let start = convert(&b_spline, Basis::BSpline, Basis::Bezier)[0];
+        assert!(start.approx_eq(Vec2::new(5., 1.), 1e-5));
+
+        // A Catmull-Rom segment interpolates its middle points
+        let bezier = convert(&b_spline, Basis::CatmullRom, Basis::Bezier);
+        assert!(bezier[0].approx_eq(b_spline[1], 1e-5));
+        assert!(bezier[3].approx_eq(b_spline[2], 1e-5));
+    }
+
+    #[test]
+    fn inverses() {
+        for basis in [
+            Basis::Bezier,
+            Basis::Hermite,
+            Basis::BSpline,
+            Basis::CatmullRom,
+        ] {
+            let product = basis.inverse_matrix() * basis.matrix();
+            let expected = basis.matrix().inverse().unwrap();
+            for i in 0..4 {
+                for j in 0..4 {
+                    let identity = if i == j { 1. } else { 0. };
+                    assert!((product.m[i][j] - identity).abs() < 1e-6);
+                    assert!((basis.inverse_matrix().m[i][j] - expected.m[i][j]).abs() < 1e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn handles() {
+        let mut curve = Bezier3::new(vec![
+            Vec2::new(0., 0.),
+            Vec2::new(1., 1.),
+            Vec2::new(2., 1.),
+            Vec2::new(3., 0.),
+        ]);
+
+        // Moving a Hermite endpoint carries its tangent along
+        curve.set_control_handle(Basis::Hermite, 0, Vec2::new(0., -1.));
+        assert!(curve.control_points[1].approx_eq(Vec2::new(1., 0.), 1e-5));
+
+        let mut handles = curve.control_handles(Basis::CatmullRom);
+        handles[3] = Vec2::new(5., 5.);
+        curve.set_control_handle(Basis::CatmullRom, 3, handles[3]);
+        for (p, q) in curve
+            .control_handles(Basis::CatmullRom)
+            .iter()
+            .zip(handles.iter())
+        {
+            assert!(p.approx_eq(*q, 1e-4));
+        }
+    }
+}
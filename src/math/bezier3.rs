@@ -1,7 +1,29 @@
-use super::{constants, solvers::newton2, vec2::Vec2};
+use super::{
+    basis::{convert, Basis},
+    constants,
+    dual::Dual,
+    interval::Interval,
+    moments::Moments,
+    polynomial::Polynomial,
+    rational::Rational,
+    real::Real,
+    solvers::{isolate_roots, newton, newton2, newton2_ad, solve_quadratic},
+    vec2::Vec2,
+};
 use crate::math::matrix2x2::Matrix2x2;
 use serde::{Deserialize, Serialize};
 
+// Stone-DeRose classification of a parametric cubic
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CurveType {
+    Serpentine,
+    Loop,
+    Cusp,
+    Quadratic,
+    Line,
+    Point,
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 pub struct Bezier3 {
     pub control_points: Vec<Vec2<f32>>,
@@ -13,7 +35,18 @@ impl Bezier3 {
         }
     }
 
-    pub fn _hull(&self) -> [f32; 4] {
+    // A straight segment from p_0 to p_1 with its handles at the thirds of the chord, so
+    // that it is traversed at constant speed
+    pub fn line(p_0: Vec2<f32>, p_1: Vec2<f32>) -> Self {
+        Self::new(vec![
+            p_0,
+            p_0 + (1. / 3.) * (p_1 - p_0),
+            p_0 + (2. / 3.) * (p_1 - p_0),
+            p_1,
+        ])
+    }
+
+    pub fn hull(&self) -> [f32; 4] {
         let mut x_min = self.control_points[0].x;
         let mut y_min = self.control_points[0].y;
         let mut x_max = self.control_points[3].x;
@@ -37,6 +70,72 @@ impl Bezier3 {
         [x_min, y_min, x_max, y_max]
     }
 
+    // Tight bounding box [x_min, y_min, x_max, y_max], including the curve's extrema
+    pub fn bounds(&self) -> [f32; 4] {
+        let p = &self.control_points;
+        let mut bounds = [
+            p[0].x.min(p[3].x),
+            p[0].y.min(p[3].y),
+            p[0].x.max(p[3].x),
+            p[0].y.max(p[3].y),
+        ];
+
+        // B'(t) / 3 = (d_0 - 2 d_1 + d_2) t^2 + 2 (d_1 - d_0) t + d_0 with d_i = P_i+1 - P_i
+        let (d_0, d_1, d_2) = (p[1] - p[0], p[2] - p[1], p[3] - p[2]);
+        let a = d_0 - 2 * d_1 + d_2;
+        let b = 2 * (d_1 - d_0);
+        let roots_x = solve_quadratic(a.x, b.x, d_0.x);
+        let roots_y = solve_quadratic(a.y, b.y, d_0.y);
+        for t in roots_x.into_iter().chain(roots_y) {
+            if (0. ..=1.).contains(&t) {
+                let q = self.get_point(t);
+                bounds = [
+                    bounds[0].min(q.x),
+                    bounds[1].min(q.y),
+                    bounds[2].max(q.x),
+                    bounds[3].max(q.y),
+                ];
+            }
+        }
+        bounds
+    }
+
+    // Apply the affine map p -> m * p + translation, which maps Bezier curves exactly
+    pub fn transform(&self, m: Matrix2x2<f32>, translation: Vec2<f32>) -> Bezier3 {
+        Bezier3::new(
+            self.control_points
+                .iter()
+                .map(|p| m * *p + translation)
+                .collect(),
+        )
+    }
+
+    // Positions of the on-canvas handles when the controls are shown in another basis.
+    // Hermite tangents are drawn at a third of their length, pointing along the curve.
+    pub fn control_handles(&self, basis: Basis) -> [Vec2<f32>; 4] {
+        let p = &self.control_points;
+        let g = convert(&[p[0], p[1], p[2], p[3]], Basis::Bezier, basis);
+        match basis {
+            Basis::Hermite => [g[0], g[1], g[0] + g[2] / 3., g[1] + g[3] / 3.],
+            _ => g,
+        }
+    }
+
+    // Move handle i of the controls shown in the given basis
+    pub fn set_control_handle(&mut self, basis: Basis, i: usize, position: Vec2<f32>) {
+        let mut h = self.control_handles(basis);
+        if basis == Basis::Hermite && i < 2 {
+            // Keep the tangent when its endpoint moves
+            h[i + 2] = h[i + 2] + (position - h[i]);
+        }
+        h[i] = position;
+        let g = match basis {
+            Basis::Hermite => [h[0], h[1], 3. * (h[2] - h[0]), 3. * (h[3] - h[1])],
+            _ => h,
+        };
+        self.control_points = convert(&g, basis, Basis::Bezier).to_vec();
+    }
+
     pub fn flatten(&self, _tolerance: f32) -> Vec<Vec2<f32>> {
         self.render_naive()
     }
@@ -67,6 +166,121 @@ impl Bezier3 {
         point
     }
 
+    // Evaluate the curve over any scalar type, e.g. Dual to get B'(t) alongside B(t)
+    pub fn evaluate<T: Real>(&self, t: T) -> Vec2<T> {
+        let s = T::from(1.) - t;
+        let weights = [
+            s * s * s,
+            T::from(3.) * s * s * t,
+            T::from(3.) * s * t * t,
+            t * t * t,
+        ];
+        let mut point = Vec2::new(T::from(0.), T::from(0.));
+        for (p, w) in self.control_points.iter().zip(weights) {
+            point = point + Vec2::new(T::from(p.x), T::from(p.y)) * w;
+        }
+        point
+    }
+
+    // Evaluate the curve in exact rational arithmetic
+    pub fn evaluate_exact(&self, t: &Rational) -> Vec2<Rational> {
+        let s = &Rational::one() - t;
+        let three = Rational::from(3);
+        let weights = [
+            &(&s * &s) * &s,
+            &(&three * &s) * &(&s * t),
+            &(&three * &s) * &(t * t),
+            &(t * t) * t,
+        ];
+        let mut point = Vec2 {
+            x: Rational::zero(),
+            y: Rational::zero(),
+        };
+        for (p, w) in self.control_points.iter().zip(weights.iter()) {
+            point = &point + &Vec2::exact(*p).scale(w);
+        }
+        point
+    }
+
+    // First derivative over any scalar type
+    pub fn evaluate_derivative<T: Real>(&self, t: T) -> Vec2<T> {
+        let s = T::from(1.) - t;
+        let weights = [s * s, T::from(2.) * s * t, t * t];
+        let mut d = Vec2::new(T::from(0.), T::from(0.));
+        for (i, w) in weights.into_iter().enumerate() {
+            let q = self.control_points[i + 1] - self.control_points[i];
+            d = d + Vec2::new(T::from(3. * q.x), T::from(3. * q.y)) * w;
+        }
+        d
+    }
+
+    // Guaranteed bounds on the curve's x and y over a range of parameters
+    pub fn range(&self, t: Interval<f32>) -> Vec2<Interval<f32>> {
+        self.evaluate(t)
+    }
+
+    // Split the curve at t using de Casteljau's algorithm
+    pub fn split(&self, t: f32) -> (Bezier3, Bezier3) {
+        let p = &self.control_points;
+        let lerp = |a: Vec2<f32>, b: Vec2<f32>| a + t * (b - a);
+
+        let p_01 = lerp(p[0], p[1]);
+        let p_12 = lerp(p[1], p[2]);
+        let p_23 = lerp(p[2], p[3]);
+        let p_012 = lerp(p_01, p_12);
+        let p_123 = lerp(p_12, p_23);
+        let p_0123 = lerp(p_012, p_123);
+
+        (
+            Bezier3::new(vec![p[0], p_01, p_012, p_0123]),
+            Bezier3::new(vec![p_0123, p_123, p_23, p[3]]),
+        )
+    }
+
+    // Return the portion of the curve between t_0 and t_1, reparameterized to [0, 1]
+    pub fn subsegment(&self, t_0: f32, t_1: f32) -> Bezier3 {
+        let (left, _) = self.split(t_1);
+        if t_1 == 0. {
+            return left;
+        }
+        let (_, middle) = left.split(t_0 / t_1);
+        middle
+    }
+
+    // Find the parameter of the point on the curve nearest to p, returned with its distance
+    pub fn nearest_point(&self, p: Vec2<f32>) -> (f32, f32) {
+        // The nearest point is an endpoint or a root of (B(t) - p) . B'(t). Interval
+        // evaluation encloses every root, so none of the candidates can be missed.
+        let residual = |t: Interval<f32>| {
+            let q = self.evaluate(t) - Vec2::new(Interval::point(p.x), Interval::point(p.y));
+            let d = self.evaluate_derivative(t);
+            q.x * d.x + q.y * d.y
+        };
+        let boxes = isolate_roots(residual, Interval::new(0., 1.), 1e-3);
+
+        // Polish each enclosure with Newton's method, staying inside the box
+        let f = |t: f32| (self.get_point(t) - p).dot(self.first_derivative(t));
+        let df = |t: f32| {
+            self.first_derivative(t).dot(self.first_derivative(t))
+                + (self.get_point(t) - p).dot(self.second_derivative(t))
+        };
+        // Newton divides by zero on a curve with coincident control points, in which case
+        // the middle of the box stands in for the root
+        let candidates = boxes
+            .iter()
+            .map(|b| {
+                let t = newton(b.midpoint(), f, df, 1e-7);
+                let t = if t.is_finite() { t } else { b.midpoint() };
+                t.clamp(b.lo, b.hi).clamp(0., 1.)
+            })
+            .chain([0., 1.]);
+
+        candidates
+            .map(|t| (t, (self.get_point(t) - p).mag()))
+            .min_by(|x, y| x.1.total_cmp(&y.1))
+            .unwrap()
+    }
+
     fn bernstein_polynomial(&self, t: f32, n: usize, i: usize) -> f32 {
         self.binomial_coefficient(n - 1, i) * t.powi(i as i32) * (1.0 - t).powi((n - 1 - i) as i32)
     }
@@ -92,10 +306,131 @@ impl Bezier3 {
                 * (self.control_points[3] - 2 * self.control_points[2] + self.control_points[1])
     }
 
+    pub fn third_derivative(&self) -> Vec2<f32> {
+        6. * (self.control_points[3] - 3 * self.control_points[2] + 3 * self.control_points[1]
+            - self.control_points[0])
+    }
+
     pub fn tangent(&self, t: f32) -> Vec2<f32> {
         self.first_derivative(t)
     }
 
+    // Power basis coefficients [a, b, c, d] of B(t) = a*t^3 + b*t^2 + c*t + d
+    fn power_basis(&self) -> [Vec2<f32>; 4] {
+        let p = &self.control_points;
+        [
+            -p[0] + 3. * p[1] - 3. * p[2] + p[3],
+            3. * p[0] - 6. * p[1] + 3. * p[2],
+            3. * (p[1] - p[0]),
+            p[0],
+        ]
+    }
+
+    // Components x(t) and y(t) of the curve in the power basis
+    pub fn components(&self) -> (Polynomial<f32>, Polynomial<f32>) {
+        let xs: Vec<f32> = self.control_points.iter().map(|p| p.x).collect();
+        let ys: Vec<f32> = self.control_points.iter().map(|p| p.y).collect();
+        (
+            Polynomial::from_bernstein(&xs),
+            Polynomial::from_bernstein(&ys),
+        )
+    }
+
+    // Squared speed |B'(t)|^2 as a quartic
+    pub fn speed_squared(&self) -> Polynomial<f32> {
+        let (x, y) = self.components();
+        let (dx, dy) = (x.derivative(), y.derivative());
+        dx.clone() * dx + dy.clone() * dy
+    }
+
+    // Coefficients of the inflection polynomial B'(t) x B''(t) = i_2*t^2 + i_1*t + i_0
+    fn inflection_polynomial(&self) -> (f32, f32, f32) {
+        let [a, b, c, _] = self.power_basis();
+        (-6. * a.cross(b), 6. * c.cross(a), 2. * c.cross(b))
+    }
+
+    // Classify the curve over its whole parameter line, following Stone and DeRose
+    pub fn classify(&self) -> CurveType {
+        let p = &self.control_points;
+        let scale = p.iter().map(|q| (*q - p[0]).mag()).fold(0., f32::max);
+        if scale <= 1e-6 {
+            return CurveType::Point;
+        }
+
+        // Normalize so that the tolerances below don't depend on the size of the curve
+        let (i_2, i_1, i_0) = self.inflection_polynomial();
+        let (i_2, i_1, i_0) = (
+            i_2 / scale.powi(2),
+            i_1 / scale.powi(2),
+            i_0 / scale.powi(2),
+        );
+        let eps = 1e-5;
+
+        if i_2.abs() <= eps && i_1.abs() <= eps {
+            if i_0.abs() <= eps {
+                CurveType::Line
+            } else {
+                CurveType::Quadratic
+            }
+        } else if i_2.abs() <= eps {
+            // One inflection, the other at infinity
+            CurveType::Serpentine
+        } else {
+            let disc = i_1.powi(2) - 4. * i_2 * i_0;
+            if disc.abs() <= eps * (i_1.powi(2) + (4. * i_2 * i_0).abs()) {
+                CurveType::Cusp
+            } else if disc > 0. {
+                CurveType::Serpentine
+            } else {
+                CurveType::Loop
+            }
+        }
+    }
+
+    // Parameters of the inflection points within [0, 1]
+    pub fn inflections(&self) -> Vec<f32> {
+        match self.classify() {
+            CurveType::Serpentine => {
+                let (i_2, i_1, i_0) = self.inflection_polynomial();
+                solve_quadratic(i_2, i_1, i_0)
+                    .into_iter()
+                    .filter(|t| (0. ..=1.).contains(t))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // Parameter of the cusp, if there is one within [0, 1]
+    pub fn cusp(&self) -> Option<f32> {
+        if self.classify() != CurveType::Cusp {
+            return None;
+        }
+        let (i_2, i_1, _) = self.inflection_polynomial();
+        let t = -i_1 / (2. * i_2);
+        (0. ..=1.).contains(&t).then_some(t)
+    }
+
+    // Parameter pair (s, t) with s < t where the curve crosses itself, if both lie in [0, 1]
+    pub fn self_intersection(&self) -> Option<(f32, f32)> {
+        if self.classify() != CurveType::Loop {
+            return None;
+        }
+
+        // B(s) - B(t) = (s - t) * (a*(s^2 + s*t + t^2) + b*(s + t) + c), so with u = s + t and
+        // v = s*t the second factor vanishes when a*(u^2 - v) + b*u + c = 0
+        let [a, b, c, _] = self.power_basis();
+        let u = c.cross(a) / a.cross(b);
+        let v = u.powi(2) + (a.dot(b) * u + a.dot(c)) / a.dot(a);
+        let roots = solve_quadratic(1., -u, v);
+        if roots.len() != 2 {
+            return None;
+        }
+
+        let (s, t) = (roots[0], roots[1]);
+        ((0. ..=1.).contains(&s) && (0. ..=1.).contains(&t)).then_some((s, t))
+    }
+
     pub fn offset_klass(&self, d: f32) -> Self {
         // Convert to hermite form
         let t_0 = 3. * (self.control_points[1] - self.control_points[0]);
@@ -110,21 +445,17 @@ impl Bezier3 {
         let alpha_0 = (1. / (1. / self.curvature(0.) - d)) * t_0.mag().powi(3);
         let alpha_1 = (1. / (1. / self.curvature(1.) - d)) * t_1.mag().powi(3);
 
-        let n_0 = 2. * (t_0 / t_1) / alpha_0;
-        let n_1 = 2. * (t_0 / t_1) / alpha_1;
-        let w_0 = 6. * (((p_1 - p_0) / t_0) + d * (t_0 * (t_1 - t_0))) / alpha_0;
-        let w_1 = 6. * (((p_0 - p_1) / t_1) + d * (t_1 * (t_0 - t_1))) / alpha_1;
+        let n_0 = 2. * t_0.cross(t_1) / alpha_0;
+        let n_1 = 2. * t_0.cross(t_1) / alpha_1;
+        let w_0 = 6. * ((p_1 - p_0).cross(t_0) + d * t_0.dot(t_1 - t_0)) / alpha_0;
+        let w_1 = 6. * ((p_0 - p_1).cross(t_1) + d * t_1.dot(t_0 - t_1)) / alpha_1;
 
-        let f = |a: f32, b: f32| -> f32 { -b.powi(2) + n_0 * a + w_0 };
-        let dfa = |_a: f32, _b: f32| -> f32 { n_0 };
-        let dfb = |_a: f32, b: f32| -> f32 { -2. * b };
-        let g = |a: f32, b: f32| -> f32 { -a.powi(2) + n_1 * b + w_1 };
-        let dga = |a: f32, _b: f32| -> f32 { -2. * a };
-        let dgb = |_a: f32, _b: f32| -> f32 { n_1 };
+        let f = |a: Dual, b: Dual| -b * b + n_0 * a + w_0;
+        let g = |a: Dual, b: Dual| -a * a + n_1 * b + w_1;
 
         let tol = 1e-7;
 
-        let (c_0, c_1) = newton2(c_0, c_1, f, dfa, dfb, g, dga, dgb, tol);
+        let (c_0, c_1) = newton2_ad(c_0, c_1, f, g, tol);
 
         // Return as a cubic bezier
         let p_0 = p_0 + d * t_0.normal().normalize();
@@ -166,7 +497,13 @@ impl Bezier3 {
             let b2 = p3.x - p2.x;
             let c2 = a2 * p3.x + b2 * p3.y;
             let det = a1 * b2 - a2 * b1;
-            assert_ne!(det, 0.);
+
+            // Parallel edges, as on a straight segment, have no intersection; the offset
+            // lines coincide, so the control point is just translated along the normal
+            if det.abs() <= 1e-6 * (a1.hypot(b1) * a2.hypot(b2)) {
+                offset_curve.push(p1.lerp(p2, 0.5));
+                continue;
+            }
             let x = (b2 * c1 - b1 * c2) / det;
             let y = (a1 * c2 - a2 * c1) / det;
             offset_curve.push(Vec2::new(x, y));
@@ -180,54 +517,395 @@ impl Bezier3 {
         Bezier3::new(offset_curve)
     }
 
+    // Signed curvature, positive where the curve turns counterclockwise
     pub fn curvature(&self, t: f32) -> f32 {
-        (self.second_derivative(t) / self.first_derivative(t))
-            / self.first_derivative(t).mag().powi(3)
+        let d_1 = self.first_derivative(t);
+        d_1.cross(self.second_derivative(t)) / d_1.mag().powi(3)
     }
 
-    pub fn offset_levien(&self, d: f32) -> Bezier3 {
-        let affine: Matrix2x2<f32> = Matrix2x2::identity();
-        // Move curve start to origin. With a 2x2 affine matrix we have to store an offset vector.
-        let offset = self.control_points[0];
+    // Signed radius of curvature, infinite where the curve is locally straight
+    pub fn radius_of_curvature(&self, t: f32) -> f32 {
+        let k = self.curvature(t);
+        if k == 0. {
+            f32::INFINITY
+        } else {
+            1. / k
+        }
+    }
 
-        // Rotate the curve so that the endpoint lies on the x-axis
-        let l = self.control_points[3] - offset;
-        let theta = l.y.atan2(l.x);
-        let affine = affine.rotation(-theta);
+    // Derivative of the signed curvature with respect to t
+    pub fn curvature_derivative(&self, t: f32) -> f32 {
+        self.curvature_derivative_numerator(t) / self.first_derivative(t).mag().powi(5)
+    }
 
-        // Scale curve so that the endpoint is at (1, 0)
-        let affine = affine.scale(1. / (affine * l).mag());
+    // dk/dt = ((B' x B''') |B'|^2 - 3 (B' x B'') (B' . B'')) / |B'|^5. The numerator is a
+    // polynomial of degree 6 that shares its zeros with dk/dt away from cusps.
+    fn curvature_derivative_numerator(&self, t: f32) -> f32 {
+        let d_1 = self.first_derivative(t);
+        let d_2 = self.second_derivative(t);
+        let d_3 = self.third_derivative();
+        d_1.cross(d_3) * d_1.dot(d_1) - 3. * d_1.cross(d_2) * d_1.dot(d_2)
+    }
 
-        // Create a new bezier object for convenience
-        let mut normalized_curve_points: Vec<Vec2<f32>> = Vec::new();
-        for p in &self.control_points {
-            normalized_curve_points.push((affine * (*p - offset)));
+    // Parameters in [0, 1] where the curvature reaches a local extremum
+    pub fn curvature_extrema(&self) -> Vec<f32> {
+        let samples = 64;
+        let f = |t: f32| self.curvature_derivative_numerator(t);
+
+        let mut extrema = Vec::new();
+        let mut t_0 = 0.;
+        let mut f_0 = f(t_0);
+        for i in 1..samples + 1 {
+            let t_1 = i as f32 / samples as f32;
+            let f_1 = f(t_1);
+
+            if f_0 == 0. {
+                // Interior sample landed exactly on a zero
+                if t_0 > 0. {
+                    extrema.push(t_0);
+                }
+            } else if f_0 * f_1 < 0. {
+                // Bisect the bracketed sign change
+                let (mut lo, mut hi, mut f_lo) = (t_0, t_1, f_0);
+                for _ in 0..32 {
+                    let mid = 0.5 * (lo + hi);
+                    let f_mid = f(mid);
+                    if f_mid * f_lo <= 0. {
+                        hi = mid;
+                    } else {
+                        lo = mid;
+                        f_lo = f_mid;
+                    }
+                }
+                extrema.push(0.5 * (lo + hi));
+            }
+
+            t_0 = t_1;
+            f_0 = f_1;
         }
-        let normalized_curve = Bezier3::new(normalized_curve_points);
+        extrema
+    }
 
-        let th_0 = normalized_curve.control_points[1]
-            .y
-            .atan2(normalized_curve.control_points[1].x);
-        let th_1 = normalized_curve.control_points[2]
-            .y
-            .atan2(1. - normalized_curve.control_points[2].x);
+    // Signed area swept between the origin and the curve, 1/2 * integral of (x dy - y dx),
+    // in closed form. Summed over a closed path this is the enclosed area.
+    pub fn signed_area(&self) -> f32 {
+        let p = &self.control_points;
+        (6. * p[0].cross(p[1])
+            + 3. * p[0].cross(p[2])
+            + p[0].cross(p[3])
+            + 3. * p[1].cross(p[2])
+            + 3. * p[1].cross(p[3])
+            + 6. * p[2].cross(p[3]))
+            / 20.
+    }
 
+    // Area, first and second moments of the region swept between the origin and the curve.
+    // By Green's theorem the integral of a degree n monomial f over that region is the
+    // integral of f(B) / (n + 2) * (B x B') dt, which Gauss-Legendre integrates exactly.
+    pub fn moments(&self) -> Moments {
+        let g_l = constants::GAUSS_LEGENDRE_COEFFS_32;
+        let mut moments = Moments::default();
+        for i in (0..g_l.len()).step_by(2) {
+            let w_i = 0.5 * g_l[i];
+            let t_i = 0.5 * (1. + g_l[i + 1]);
+            let p = self.get_point(t_i);
+            let cross = w_i * p.cross(self.first_derivative(t_i));
+
+            moments.area += cross / 2.;
+            moments.x += p.x * cross / 3.;
+            moments.y += p.y * cross / 3.;
+            moments.xx += p.x * p.x * cross / 4.;
+            moments.xy += p.x * p.y * cross / 4.;
+            moments.yy += p.y * p.y * cross / 4.;
+        }
+        moments
+    }
+
+    // Levien's offset: the cubic with the exact offset curve's end points and end tangents
+    // whose area and x-moment, measured against its chord, match those of the exact offset
+    pub fn offset_levien(&self, d: f32) -> Bezier3 {
+        // The exact offset B + d * N and its derivative, with N the unit left normal
+        let offset_point = |t: f32| {
+            let d_1 = self.first_derivative(t);
+            self.get_point(t) + d * (d_1.normal() / d_1.mag())
+        };
+        let offset_derivative = |t: f32| {
+            let (d_1, d_2) = (self.first_derivative(t), self.second_derivative(t));
+            let speed = d_1.mag();
+            d_1 + d * (d_2.normal() / speed - d_1.normal() * (d_1.dot(d_2) / speed.powi(3)))
+        };
+
+        // Move the offset's start to the origin. With a 2x2 affine matrix we have to store an
+        // offset vector.
+        let offset = offset_point(0.);
+
+        // Rotate and scale so that the offset's end lies at (1, 0)
+        let l = offset_point(1.) - offset;
+        if l.try_normalize().is_none() {
+            return self.offset_tiller(d);
+        }
+        let theta = l.y.atan2(l.x);
+        let affine = Matrix2x2::identity().rotation(-theta).scale(1. / l.mag());
+
+        // Area under the normalized exact offset and its x-moment
         let g_l = constants::GAUSS_LEGENDRE_COEFFS_32;
         let mut area = 0.;
         let mut x_moment = 0.;
         for i in (0..g_l.len()).step_by(2) {
-            let w_i = g_l[i];
-            let x_i = 0.5 * (1. + g_l[i + 1]);
-            let p = normalized_curve.get_point(x_i);
-            let dp = normalized_curve.first_derivative(x_i);
-            let da = w_i * dp.x * p.y;
+            let w_i = 0.5 * g_l[i];
+            let t_i = 0.5 * (1. + g_l[i + 1]);
+            let p = affine * (offset_point(t_i) - offset);
+            let da = w_i * (affine * offset_derivative(t_i)).x * p.y;
             area += da;
             x_moment += p.x * da;
         }
 
-        area /= 2.;
-        x_moment /= 2.;
+        // The offset leaves and arrives along the source curve's end tangents
+        let start = affine * self.first_derivative(0.);
+        let end = affine * self.first_derivative(1.);
+        let th_0 = start.y.atan2(start.x);
+        let th_1 = (-end.y).atan2(end.x);
+        let candidate = |d_0: f32, d_1: f32| {
+            Bezier3::new(vec![
+                Vec2::new(0., 0.),
+                d_0 * Vec2::new(th_0.cos(), th_0.sin()),
+                Vec2::new(1. - d_1 * th_1.cos(), d_1 * th_1.sin()),
+                Vec2::new(1., 0.),
+            ])
+        };
+
+        // The chord along the x-axis adds nothing to the moments, so the area under a
+        // normalized candidate and its x-moment follow directly from the swept moments
+        let residual = |d_0: f32, d_1: f32| {
+            let moments = candidate(d_0, d_1).moments();
+            (-moments.area - area, -moments.x - x_moment)
+        };
+        let f = |d_0: f32, d_1: f32| residual(d_0, d_1).0;
+        let g = |d_0: f32, d_1: f32| residual(d_0, d_1).1;
+        let h = 1e-3;
+
+        // Solve for the handle lengths, starting from those of the source curve
+        let p = &self.control_points;
+        let guess = (
+            (affine * (p[1] - p[0])).mag(),
+            (affine * (p[3] - p[2])).mag(),
+        );
+        let (d_0, d_1) = newton2(
+            guess.0,
+            guess.1,
+            f,
+            |x, y| (f(x + h, y) - f(x - h, y)) / (2. * h),
+            |x, y| (f(x, y + h) - f(x, y - h)) / (2. * h),
+            g,
+            |x, y| (g(x + h, y) - g(x - h, y)) / (2. * h),
+            |x, y| (g(x, y + h) - g(x, y - h)) / (2. * h),
+            1e-5,
+        );
+
+        // Without a solution with forward pointing handles, keep the source's proportions
+        let (d_0, d_1) = if d_0 >= 0. && d_1 >= 0. && d_0.is_finite() && d_1.is_finite() {
+            (d_0, d_1)
+        } else {
+            guess
+        };
+
+        let inverse = affine.inverse();
+        Bezier3::new(
+            candidate(d_0, d_1)
+                .control_points
+                .iter()
+                .map(|q| inverse * *q + offset)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod bezier3_tests {
+    use crate::math::bezier3::CurveType;
+    use crate::math::dual::HyperDual;
+    use crate::math::fixtures::curve;
+    use crate::math::interval::Interval;
+    use crate::math::rational::Rational;
+    use crate::math::vec2::Vec2;
+
+    #[test]
+    fn classification() {
+        let serpentine = curve([(0., 0.), (0.3, 1.), (0.7, -1.), (1., 0.)]);
+        assert_eq!(serpentine.classify(), CurveType::Serpentine);
+        let inflections = serpentine.inflections();
+        assert_eq!(inflections.len(), 1);
+        assert!((inflections[0] - 0.5).abs() < 1e-4);
+
+        // B'(0.5) vanishes when P3 = P0 + P1 - P2
+        let cusp = curve([(0., 0.), (1., 1.), (0., 1.), (1., 0.)]);
+        assert_eq!(cusp.classify(), CurveType::Cusp);
+        assert!((cusp.cusp().unwrap() - 0.5).abs() < 1e-4);
+
+        let looped = curve([(0., 0.), (1.5, 1.), (-0.5, 1.), (1., 0.)]);
+        assert_eq!(looped.classify(), CurveType::Loop);
+        let (s, t) = looped.self_intersection().unwrap();
+        assert!(s < t);
+        assert!((looped.get_point(s) - looped.get_point(t)).mag() < 1e-4);
+
+        // The arch is part of a loop that closes outside [0, 1]
+        let arch = curve([(0., 0.), (0., 1.), (1., 1.), (1., 0.)]);
+        assert_eq!(arch.classify(), CurveType::Loop);
+        assert_eq!(arch.self_intersection(), None);
+
+        let quadratic = curve([(0., 0.), (1. / 3., 2. / 3.), (2. / 3., 2. / 3.), (1., 0.)]);
+        assert_eq!(quadratic.classify(), CurveType::Quadratic);
+
+        let line = curve([(0., 0.), (0.2, 0.4), (0.3, 0.6), (1., 2.)]);
+        assert_eq!(line.classify(), CurveType::Line);
+
+        let point = curve([(0.5, 0.5); 4]);
+        assert_eq!(point.classify(), CurveType::Point);
+    }
+
+    #[test]
+    fn power_basis_components() {
+        let c = curve([(0.2, 0.2), (0.1, 0.9), (0.4, 0.4), (0.9, 0.8)]);
+        let (x, y) = c.components();
+        let speed_squared = c.speed_squared();
+        for i in 0..=10 {
+            let t = i as f32 / 10.;
+            let p = c.get_point(t);
+            assert!((x.evaluate(t) - p.x).abs() < 1e-5 && (y.evaluate(t) - p.y).abs() < 1e-5);
+            let d = c.first_derivative(t);
+            assert!((speed_squared.evaluate(t) - d.dot(d)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn automatic_derivatives() {
+        let c = curve([(0.2, 0.2), (0.1, 0.9), (0.4, 0.4), (0.9, 0.8)]);
+        for i in 0..=10 {
+            let t = i as f32 / 10.;
+            let p = c.evaluate(HyperDual::variable(t));
+            let (d_1, d_2) = (c.first_derivative(t), c.second_derivative(t));
+            assert!(Vec2::new(p.x.re, p.y.re).approx_eq(c.get_point(t), 1e-5));
+            assert!(Vec2::new(p.x.e1, p.y.e1).approx_eq(d_1, 1e-4));
+            assert!(Vec2::new(p.x.e12, p.y.e12).approx_eq(d_2, 1e-4));
+        }
+    }
+
+    #[test]
+    fn certified_bounds() {
+        let c = curve([(0.2, 0.2), (0.1, 0.9), (0.4, 0.4), (0.9, 0.8)]);
+        let range = c.range(Interval::new(0.25, 0.5));
+        for i in 0..=10 {
+            let p = c.get_point(0.25 + 0.025 * i as f32);
+            assert!(range.x.contains(p.x) && range.y.contains(p.y));
+        }
+
+        // The foot of the perpendicular from a point near the middle of an arch
+        let arch = curve([(0., 0.), (0., 1.), (1., 1.), (1., 0.)]);
+        let (t, d) = arch.nearest_point(Vec2::new(0.5, 0.5));
+        assert!((t - 0.5).abs() < 1e-4 && (d - 0.25).abs() < 1e-5);
+
+        // A curve collapsed to a point is at the same distance for every parameter
+        let point = curve([(0.5, 0.5); 4]);
+        let (t, d) = point.nearest_point(Vec2::new(0.8, 0.9));
+        assert!((0. ..=1.).contains(&t) && (d - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn exact_evaluation() {
+        let c = curve([(0.2, 0.2), (0.1, 0.9), (0.4, 0.4), (0.9, 0.8)]);
+        let t = Rational::from_f32(0.375).unwrap();
+        let p = c.evaluate_exact(&t);
+        assert!(p.to_f32().approx_eq(c.get_point(0.375), 1e-6));
+
+        // The endpoints come back bit for bit
+        let end = c.evaluate_exact(&Rational::one());
+        assert_eq!(end.to_f32(), c.control_points[3]);
+    }
+
+    #[test]
+    fn curvature_circle() {
+        // Cubic approximation of a counterclockwise 30 degree arc of radius 2
+        let r = 2.;
+        let theta = std::f32::consts::PI / 6.;
+        let k = 4. / 3. * (theta / 4.).tan();
+        let arc = curve([
+            (r, 0.),
+            (r, r * k),
+            (
+                r * (theta.cos() + k * theta.sin()),
+                r * (theta.sin() - k * theta.cos()),
+            ),
+            (r * theta.cos(), r * theta.sin()),
+        ]);
+        for i in 0..11 {
+            let t = i as f32 / 10.;
+            assert!((arc.curvature(t) - 1. / r).abs() < 1e-3);
+            assert!((arc.radius_of_curvature(t) - r).abs() < 1e-2);
+        }
+
+        // Reversing the direction of travel flips the sign
+        let mut reversed = arc.clone();
+        reversed.control_points.reverse();
+        assert!((reversed.curvature(0.5) + 1. / r).abs() < 1e-3);
+    }
+
+    #[test]
+    fn curvature_parabola() {
+        // y = x^2 for x in [-1, 1], degree elevated from a quadratic so that x = 2t - 1
+        let parabola = curve([
+            (-1., 1.),
+            (-1. / 3., -1. / 3.),
+            (1. / 3., -1. / 3.),
+            (1., 1.),
+        ]);
+        for i in 0..11 {
+            let t = i as f32 / 10.;
+            let x = 2. * t - 1.;
+            let k = 2. / (1. + 4. * x * x).powf(1.5);
+            let dk_dx = -24. * x / (1. + 4. * x * x).powf(2.5);
+            assert!((parabola.curvature(t) - k).abs() < 1e-4);
+            assert!((parabola.curvature_derivative(t) - 2. * dk_dx).abs() < 1e-3);
+        }
+        assert!((parabola.radius_of_curvature(0.5) - 0.5).abs() < 1e-5);
 
-        normalized_curve
+        // The only extremum is at the vertex
+        let extrema = parabola.curvature_extrema();
+        assert_eq!(extrema.len(), 1);
+        assert!((extrema[0] - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn straight_offsets() {
+        // Every edge of a straight segment's control polygon is parallel
+        let line = curve([(0.2, 0.5), (0.4, 0.5), (0.6, 0.5), (0.8, 0.5)]);
+        let offset = line.offset_tiller(0.1);
+        let y = offset.control_points[0].y;
+        assert!((y - 0.5).abs() > 0.099 && (y - 0.5).abs() < 0.101);
+        for (p, q) in offset.control_points.iter().zip(&line.control_points) {
+            assert!(p.approx_eq(Vec2::new(q.x, y), 1e-6));
+        }
+    }
+
+    #[test]
+    fn levien_offsets() {
+        // A quarter circle of radius 1, counterclockwise, whose left offset is a circle of
+        // radius 1 - d
+        let k = 0.5522848;
+        let arc = curve([(1., 0.), (1., k), (k, 1.), (0., 1.)]);
+        let offset = arc.offset_levien(0.2);
+        assert!(offset.control_points[0].approx_eq(Vec2::new(0.8, 0.), 1e-5));
+        assert!(offset.control_points[3].approx_eq(Vec2::new(0., 0.8), 1e-5));
+        for i in 0..=10 {
+            let r = offset.get_point(i as f32 / 10.).mag();
+            assert!((r - 0.8).abs() < 1e-3);
+        }
+
+        // A general curve's offset stays close to the offset distance from it
+        let c = curve([(0.2, 0.2), (0.4, 0.9), (0.7, 0.5), (0.9, 0.8)]);
+        let offset = c.offset_levien(0.05);
+        for i in 0..=10 {
+            let (_, distance) = c.nearest_point(offset.get_point(i as f32 / 10.));
+            assert!((distance - 0.05).abs() < 2e-3);
+        }
     }
 }
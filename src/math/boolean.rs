@@ -0,0 +1,204 @@
+use super::{
+    bezier3::Bezier3,
+    intersection::curve_curve,
+    path::{FillRule, Path},
+};
+
+// Distance below which points are treated as coincident
+const TOLERANCE: f32 = 1e-4;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    Difference,
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(&self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            BooleanOp::Union => in_a || in_b,
+            BooleanOp::Intersection => in_a && in_b,
+            BooleanOp::Difference => in_a && !in_b,
+            BooleanOp::Xor => in_a != in_b,
+        }
+    }
+}
+
+// Combine the regions enclosed by two paths under the nonzero fill rule. The result is a
+// set of closed counterclockwise loops with holes running clockwise.
+pub fn boolean(a: &Path, b: &Path, op: BooleanOp) -> Vec<Path> {
+    let segments_a = a.closed_segments();
+    let segments_b = b.closed_segments();
+
+    // Find every crossing between the two boundaries
+    let mut splits_a: Vec<Vec<f32>> = vec![Vec::new(); segments_a.len()];
+    let mut splits_b: Vec<Vec<f32>> = vec![Vec::new(); segments_b.len()];
+    for (i, s_a) in segments_a.iter().enumerate() {
+        for (j, s_b) in segments_b.iter().enumerate() {
+            for (s, t) in curve_curve(s_a, s_b, TOLERANCE) {
+                splits_a[i].push(s);
+                splits_b[j].push(t);
+            }
+        }
+    }
+
+    // Cut both boundaries into pieces that lie entirely inside or outside the other region
+    let pieces_a = split_segments(&segments_a, &mut splits_a);
+    let pieces_b = split_segments(&segments_b, &mut splits_b);
+
+    // Keep the pieces that separate the result from its complement, oriented so that the
+    // result lies on their left. Where the boundaries coincide, B's copy is dropped.
+    let inside = |p| {
+        op.apply(
+            a.contains(p, FillRule::NonZero),
+            b.contains(p, FillRule::NonZero),
+        )
+    };
+    let mut kept = Vec::new();
+    for (piece, from_b) in pieces_a
+        .into_iter()
+        .map(|p| (p, false))
+        .chain(pieces_b.into_iter().map(|p| (p, true)))
+    {
+        let m = piece.get_point(0.5);
+        if from_b && on_boundary(&segments_a, &piece) {
+            continue;
+        }
+
+        // Where the tangent vanishes at the midpoint, the chord still gives the direction
+        let chord = piece.get_point(1.) - piece.get_point(0.);
+        let normal = match piece.tangent(0.5).try_normalize().or(chord.try_normalize()) {
+            Some(direction) => direction.normal(),
+            None => continue,
+        };
+        let left = inside(m + 10. * TOLERANCE * normal);
+        let right = inside(m - 10. * TOLERANCE * normal);
+        if left && !right {
+            kept.push(piece);
+        } else if right && !left {
+            let mut piece = piece;
+            piece.control_points.reverse();
+            kept.push(piece);
+        }
+    }
+
+    chain_loops(kept)
+}
+
+fn split_segments(segments: &[Bezier3], splits: &mut [Vec<f32>]) -> Vec<Bezier3> {
+    let mut pieces = Vec::new();
+    for (segment, params) in segments.iter().zip(splits.iter_mut()) {
+        params.sort_by(|x, y| x.total_cmp(y));
+
+        let mut t_0 = 0.;
+        for &t in params.iter().chain([1.].iter()) {
+            let piece = segment.subsegment(t_0, t);
+            if (piece.get_point(0.) - piece.get_point(1.)).mag() > TOLERANCE
+                || (piece.get_point(0.5) - piece.get_point(0.)).mag() > TOLERANCE
+            {
+                pieces.push(piece);
+                t_0 = t;
+            }
+        }
+    }
+    pieces
+}
+
+fn on_boundary(segments: &[Bezier3], piece: &Bezier3) -> bool {
+    let m = piece.get_point(0.5);
+    segments
+        .iter()
+        .any(|s| s.nearest_point(m).1 <= 10. * TOLERANCE)
+}
+
+// Link pieces end to start into closed loops
+fn chain_loops(mut pieces: Vec<Bezier3>) -> Vec<Path> {
+    let mut loops = Vec::new();
+    while let Some(first) = pieces.pop() {
+        let start = first.control_points[0];
+        let mut path = Path::new(vec![first], true);
+
+        loop {
+            let end = path.end().unwrap();
+            if (end - start).mag() <= 10. * TOLERANCE && path.len() > 1 {
+                break;
+            }
+
+            // Continue with the piece that starts closest to the current end
+            let next = pieces
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (i, (p.control_points[0] - end).mag()))
+                .min_by(|x, y| x.1.total_cmp(&y.1));
+            match next {
+                Some((i, d)) if d <= 10. * TOLERANCE => path.push(pieces.swap_remove(i)),
+                _ => break,
+            }
+        }
+
+        // Weld the loop shut
+        let last = path.segments.len() - 1;
+        path.segments[last].control_points[3] = start;
+        loops.push(path);
+    }
+    loops
+}
+
+#[cfg(test)]
+mod boolean_tests {
+    use crate::math::boolean::{boolean, BooleanOp};
+    use crate::math::fixtures::square;
+    use crate::math::path::Path;
+
+    fn area(paths: &[Path]) -> f32 {
+        paths.iter().map(|p| p.signed_area()).sum()
+    }
+
+    #[test]
+    fn overlapping_squares() {
+        let a = square(0., 0., 2.);
+        let b = square(1., 1., 2.);
+
+        let union = boolean(&a, &b, BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert!((area(&union) - 7.).abs() < 1e-3);
+
+        let intersection = boolean(&a, &b, BooleanOp::Intersection);
+        assert_eq!(intersection.len(), 1);
+        assert!((area(&intersection) - 1.).abs() < 1e-3);
+
+        let difference = boolean(&a, &b, BooleanOp::Difference);
+        assert_eq!(difference.len(), 1);
+        assert!((area(&difference) - 3.).abs() < 1e-3);
+
+        assert!((area(&boolean(&a, &b, BooleanOp::Xor)) - 6.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn nested_and_disjoint() {
+        let outer = square(0., 0., 3.);
+        let inner = square(1., 1., 1.);
+        let apart = square(5., 5., 1.);
+
+        // Cutting a hole leaves an outer loop and a clockwise inner loop
+        let holed = boolean(&outer, &inner, BooleanOp::Difference);
+        assert_eq!(holed.len(), 2);
+        assert!((area(&holed) - 8.).abs() < 1e-3);
+
+        assert!((area(&boolean(&outer, &inner, BooleanOp::Union)) - 9.).abs() < 1e-3);
+        assert!((area(&boolean(&outer, &apart, BooleanOp::Union)) - 10.).abs() < 1e-3);
+        assert!(boolean(&outer, &apart, BooleanOp::Intersection).is_empty());
+    }
+
+    #[test]
+    fn shared_edges() {
+        // Two squares side by side merge into one rectangle
+        let a = square(0., 0., 1.);
+        let b = square(1., 0., 1.);
+        let union = boolean(&a, &b, BooleanOp::Union);
+        assert!((area(&union) - 2.).abs() < 1e-3);
+        assert!(boolean(&a, &b, BooleanOp::Intersection).is_empty());
+    }
+}
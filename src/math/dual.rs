@@ -0,0 +1,411 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::*;
+
+// Dual number re + eps*e with e^2 = 0. Evaluating a function at Dual::variable(x) carries
+// its derivative at x along in the eps part (forward-mode automatic differentiation).
+#[derive(Copy, Clone, Debug)]
+pub struct Dual {
+    pub re: f32,
+    pub eps: f32,
+}
+
+// Hyper-dual number re + e1*a + e2*b + e12*ab with a^2 = b^2 = 0. Seeding both a and b with
+// HyperDual::variable(x) gives the first derivative in e1 (and e2) and the second in e12.
+#[derive(Copy, Clone, Debug)]
+pub struct HyperDual {
+    pub re: f32,
+    pub e1: f32,
+    pub e2: f32,
+    pub e12: f32,
+}
+
+impl Dual {
+    pub const fn new(re: f32, eps: f32) -> Self {
+        Self { re, eps }
+    }
+
+    pub const fn constant(re: f32) -> Self {
+        Self::new(re, 0.)
+    }
+
+    // The independent variable, whose derivative is 1
+    pub const fn variable(re: f32) -> Self {
+        Self::new(re, 1.)
+    }
+
+    // Apply a scalar function given its value and derivative at re
+    fn chain(&self, f: f32, df: f32) -> Self {
+        Self::new(f, df * self.eps)
+    }
+
+    pub fn sqrt(&self) -> Self {
+        let s = self.re.sqrt();
+        self.chain(s, 0.5 / s)
+    }
+
+    pub fn sin(&self) -> Self {
+        self.chain(self.re.sin(), self.re.cos())
+    }
+
+    pub fn cos(&self) -> Self {
+        self.chain(self.re.cos(), -self.re.sin())
+    }
+
+    pub fn powi(&self, n: i32) -> Self {
+        self.chain(self.re.powi(n), n as f32 * self.re.powi(n - 1))
+    }
+
+    pub fn abs(&self) -> Self {
+        if self.re < 0. {
+            -*self
+        } else {
+            *self
+        }
+    }
+}
+
+impl HyperDual {
+    pub const fn new(re: f32, e1: f32, e2: f32, e12: f32) -> Self {
+        Self { re, e1, e2, e12 }
+    }
+
+    pub const fn constant(re: f32) -> Self {
+        Self::new(re, 0., 0., 0.)
+    }
+
+    pub const fn variable(re: f32) -> Self {
+        Self::new(re, 1., 1., 0.)
+    }
+
+    // Apply a scalar function given its value and first two derivatives at re
+    fn chain(&self, f: f32, df: f32, ddf: f32) -> Self {
+        Self::new(
+            f,
+            df * self.e1,
+            df * self.e2,
+            df * self.e12 + ddf * self.e1 * self.e2,
+        )
+    }
+
+    pub fn sqrt(&self) -> Self {
+        let s = self.re.sqrt();
+        self.chain(s, 0.5 / s, -0.25 / (s * self.re))
+    }
+
+    pub fn sin(&self) -> Self {
+        let (sin, cos) = self.re.sin_cos();
+        self.chain(sin, cos, -sin)
+    }
+
+    pub fn cos(&self) -> Self {
+        let (sin, cos) = self.re.sin_cos();
+        self.chain(cos, -sin, -cos)
+    }
+
+    pub fn powi(&self, n: i32) -> Self {
+        self.chain(
+            self.re.powi(n),
+            n as f32 * self.re.powi(n - 1),
+            (n * (n - 1)) as f32 * self.re.powi(n - 2),
+        )
+    }
+
+    pub fn abs(&self) -> Self {
+        if self.re < 0. {
+            -*self
+        } else {
+            *self
+        }
+    }
+}
+
+impl fmt::Display for Dual {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} + {}e", self.re, self.eps)
+    }
+}
+
+impl fmt::Display for HyperDual {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} + {}a + {}b + {}ab",
+            self.re, self.e1, self.e2, self.e12
+        )
+    }
+}
+
+// Conversions required by Real: constants come in with zero derivative, and converting
+// back keeps only the value
+impl From<f32> for Dual {
+    fn from(re: f32) -> Self {
+        Dual::constant(re)
+    }
+}
+
+impl From<Dual> for f32 {
+    fn from(x: Dual) -> f32 {
+        x.re
+    }
+}
+
+impl From<f32> for HyperDual {
+    fn from(re: f32) -> Self {
+        HyperDual::constant(re)
+    }
+}
+
+impl From<HyperDual> for f32 {
+    fn from(x: HyperDual) -> f32 {
+        x.re
+    }
+}
+
+// Compare and order by value alone, as the derivatives say nothing about which number is
+// larger. This keeps comparisons such as t <= 0 or t == 0 behaving the same under
+// differentiation as without it, and keeps == consistent with the ordering.
+impl PartialEq for Dual {
+    fn eq(&self, other: &Dual) -> bool {
+        self.re == other.re
+    }
+}
+
+impl PartialEq for HyperDual {
+    fn eq(&self, other: &HyperDual) -> bool {
+        self.re == other.re
+    }
+}
+
+impl PartialOrd for Dual {
+    fn partial_cmp(&self, other: &Dual) -> Option<Ordering> {
+        self.re.partial_cmp(&other.re)
+    }
+}
+
+impl PartialOrd for HyperDual {
+    fn partial_cmp(&self, other: &HyperDual) -> Option<Ordering> {
+        self.re.partial_cmp(&other.re)
+    }
+}
+
+// Negation: -Dual -> Dual
+impl Neg for Dual {
+    type Output = Dual;
+    fn neg(self) -> Dual {
+        Dual::new(-self.re, -self.eps)
+    }
+}
+
+// Addition: Dual + Dual -> Dual
+impl Add for Dual {
+    type Output = Dual;
+    fn add(self, rhs: Dual) -> Dual {
+        Dual::new(self.re + rhs.re, self.eps + rhs.eps)
+    }
+}
+
+// Subtraction: Dual - Dual -> Dual
+impl Sub for Dual {
+    type Output = Dual;
+    fn sub(self, rhs: Dual) -> Dual {
+        Dual::new(self.re - rhs.re, self.eps - rhs.eps)
+    }
+}
+
+// Multiplication: Dual * Dual -> Dual, by the product rule
+impl Mul for Dual {
+    type Output = Dual;
+    fn mul(self, rhs: Dual) -> Dual {
+        Dual::new(self.re * rhs.re, self.re * rhs.eps + self.eps * rhs.re)
+    }
+}
+
+// Division: Dual / Dual -> Dual, by the quotient rule
+impl Div for Dual {
+    type Output = Dual;
+    fn div(self, rhs: Dual) -> Dual {
+        Dual::new(
+            self.re / rhs.re,
+            (self.eps * rhs.re - self.re * rhs.eps) / (rhs.re * rhs.re),
+        )
+    }
+}
+
+// Negation: -HyperDual -> HyperDual
+impl Neg for HyperDual {
+    type Output = HyperDual;
+    fn neg(self) -> HyperDual {
+        HyperDual::new(-self.re, -self.e1, -self.e2, -self.e12)
+    }
+}
+
+// Addition: HyperDual + HyperDual -> HyperDual
+impl Add for HyperDual {
+    type Output = HyperDual;
+    fn add(self, rhs: HyperDual) -> HyperDual {
+        HyperDual::new(
+            self.re + rhs.re,
+            self.e1 + rhs.e1,
+            self.e2 + rhs.e2,
+            self.e12 + rhs.e12,
+        )
+    }
+}
+
+// Subtraction: HyperDual - HyperDual -> HyperDual
+impl Sub for HyperDual {
+    type Output = HyperDual;
+    fn sub(self, rhs: HyperDual) -> HyperDual {
+        self + -rhs
+    }
+}
+
+// Multiplication: HyperDual * HyperDual -> HyperDual
+impl Mul for HyperDual {
+    type Output = HyperDual;
+    fn mul(self, rhs: HyperDual) -> HyperDual {
+        HyperDual::new(
+            self.re * rhs.re,
+            self.re * rhs.e1 + self.e1 * rhs.re,
+            self.re * rhs.e2 + self.e2 * rhs.re,
+            self.re * rhs.e12 + self.e1 * rhs.e2 + self.e2 * rhs.e1 + self.e12 * rhs.re,
+        )
+    }
+}
+
+// Division: HyperDual / HyperDual -> HyperDual, as multiplication by the reciprocal
+impl Div for HyperDual {
+    type Output = HyperDual;
+    fn div(self, rhs: HyperDual) -> HyperDual {
+        let r = 1. / rhs.re;
+        self * rhs.chain(r, -r * r, 2. * r * r * r)
+    }
+}
+
+// Compound assignment and mixed operations with f32 for both types
+macro_rules! dual_ops {
+    ($t:ty) => {
+        impl AddAssign for $t {
+            fn add_assign(&mut self, rhs: $t) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl SubAssign for $t {
+            fn sub_assign(&mut self, rhs: $t) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl MulAssign for $t {
+            fn mul_assign(&mut self, rhs: $t) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl DivAssign for $t {
+            fn div_assign(&mut self, rhs: $t) {
+                *self = *self / rhs;
+            }
+        }
+
+        impl Add<f32> for $t {
+            type Output = $t;
+            fn add(self, rhs: f32) -> $t {
+                self + <$t>::from(rhs)
+            }
+        }
+
+        impl Add<$t> for f32 {
+            type Output = $t;
+            fn add(self, rhs: $t) -> $t {
+                <$t>::from(self) + rhs
+            }
+        }
+
+        impl Sub<f32> for $t {
+            type Output = $t;
+            fn sub(self, rhs: f32) -> $t {
+                self - <$t>::from(rhs)
+            }
+        }
+
+        impl Sub<$t> for f32 {
+            type Output = $t;
+            fn sub(self, rhs: $t) -> $t {
+                <$t>::from(self) - rhs
+            }
+        }
+
+        impl Mul<f32> for $t {
+            type Output = $t;
+            fn mul(self, rhs: f32) -> $t {
+                self * <$t>::from(rhs)
+            }
+        }
+
+        impl Mul<$t> for f32 {
+            type Output = $t;
+            fn mul(self, rhs: $t) -> $t {
+                <$t>::from(self) * rhs
+            }
+        }
+
+        impl Div<f32> for $t {
+            type Output = $t;
+            fn div(self, rhs: f32) -> $t {
+                self / <$t>::from(rhs)
+            }
+        }
+
+        impl Div<$t> for f32 {
+            type Output = $t;
+            fn div(self, rhs: $t) -> $t {
+                <$t>::from(self) / rhs
+            }
+        }
+    };
+}
+
+dual_ops!(Dual);
+dual_ops!(HyperDual);
+
+#[cfg(test)]
+mod dual_tests {
+    use crate::math::dual::{Dual, HyperDual};
+
+    #[test]
+    fn first_derivatives() {
+        // d/dx x^3 / (1 + x) at x = 2 is (3x^2 (1 + x) - x^3) / (1 + x)^2 = 28/9
+        let x = Dual::variable(2.);
+        let y = x * x * x / (1. + x);
+        assert!((y.re - 8. / 3.).abs() < 1e-6);
+        assert!((y.eps - 28. / 9.).abs() < 1e-5);
+
+        let y = x.powi(3) - 2. * x.sqrt();
+        assert!((y.eps - (12. - 1. / 2_f32.sqrt())).abs() < 1e-5);
+        assert!(Dual::constant(2.) < Dual::constant(3.));
+
+        // Only the values are compared, whatever the derivatives
+        assert!(Dual::variable(0.) <= 0.0.into());
+        assert!(HyperDual::variable(1.) >= 1.0.into());
+        assert_eq!(Dual::new(1., 0.), Dual::new(1., 5.));
+        assert_eq!(HyperDual::variable(2.), HyperDual::constant(2.));
+        assert_ne!(Dual::variable(1.), Dual::variable(2.));
+    }
+
+    #[test]
+    fn second_derivatives() {
+        // f(x) = x sin(x), f' = sin(x) + x cos(x), f'' = 2 cos(x) - x sin(x)
+        let x_0: f32 = 0.7;
+        let x = HyperDual::variable(x_0);
+        let y = x * x.sin();
+        assert!((y.e1 - (x_0.sin() + x_0 * x_0.cos())).abs() < 1e-6);
+        assert!((y.e12 - (2. * x_0.cos() - x_0 * x_0.sin())).abs() < 1e-6);
+
+        // (1/x)'' = 2/x^3
+        let y = 1. / HyperDual::variable(2.);
+        assert!((y.e1 + 0.25).abs() < 1e-6 && (y.e12 - 0.25).abs() < 1e-6);
+    }
+}
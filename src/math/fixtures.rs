@@ -0,0 +1,22 @@
+// Shapes shared by the tests of the curve and geometry modules
+use super::{bezier3::Bezier3, path::Path, vec2::Vec2};
+
+pub fn curve(points: [(f32, f32); 4]) -> Bezier3 {
+    Bezier3::new(points.iter().map(|p| Vec2::new(p.0, p.1)).collect())
+}
+
+// Counterclockwise square of straight segments with its lower left corner at (x_0, y_0)
+pub fn square(x_0: f32, y_0: f32, size: f32) -> Path {
+    let corners = [
+        Vec2::new(x_0, y_0),
+        Vec2::new(x_0 + size, y_0),
+        Vec2::new(x_0 + size, y_0 + size),
+        Vec2::new(x_0, y_0 + size),
+    ];
+    Path::new(
+        (0..4)
+            .map(|i| Bezier3::line(corners[i], corners[(i + 1) % 4]))
+            .collect(),
+        true,
+    )
+}
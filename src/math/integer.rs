@@ -1,26 +1,330 @@
-use std::fmt::Display;
+use std::cmp::Ordering;
+use std::fmt;
 use std::ops::*;
 
-macro_rules! items {
-    ($($item:item)*) => ($($item)*);
+// Arbitrary precision signed integer, stored as a sign and little-endian 32-bit limbs
+// without leading zero limbs. Zero has no limbs and is never negative.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
 }
 
-macro_rules! trait_alias {
-    ($name:ident = $($base:tt)+) => {
-        items! {
-            pub trait $name: $($base)+ { }
-            impl<T: $($base)+> $name for T { }
+fn trim(v: &mut Vec<u32>) {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    result.push(carry as u32);
+    trim(&mut result);
+    result
+}
+
+// a - b for |a| >= |b|
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &a_i) in a.iter().enumerate() {
+        let mut diff = a_i as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+    trim(&mut result);
+    result
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, &a_i) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &b_j) in b.iter().enumerate() {
+            let product = a_i as u64 * b_j as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    trim(&mut result);
+    result
+}
+
+// Quotient and remainder of |a| / |b| by binary long division
+fn divrem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    assert!(!b.is_empty(), "division by zero");
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+    for bit in (0..32 * a.len()).rev() {
+        // remainder = 2 * remainder + next bit of a
+        let mut carry = (a[bit / 32] >> (bit % 32)) & 1;
+        for limb in remainder.iter_mut() {
+            let next = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = next;
+        }
+        if carry != 0 {
+            remainder.push(carry);
+        }
+
+        if cmp_magnitude(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitude(&remainder, b);
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    trim(&mut quotient);
+    (quotient, remainder)
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        trim(&mut magnitude);
+        let negative = negative && !magnitude.is_empty();
+        Self {
+            negative,
+            magnitude,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::from_parts(false, Vec::new())
+    }
+
+    pub fn one() -> Self {
+        Self::from(1)
+    }
+
+    // 2^k
+    pub fn pow2(k: usize) -> Self {
+        let mut magnitude = vec![0u32; k / 32 + 1];
+        magnitude[k / 32] = 1 << (k % 32);
+        Self::from_parts(false, magnitude)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.magnitude.clone())
+    }
+
+    // Quotient rounded toward zero and the remainder, which takes the sign of self
+    pub fn div_rem(&self, rhs: &BigInt) -> (BigInt, BigInt) {
+        let (q, r) = divrem_magnitude(&self.magnitude, &rhs.magnitude);
+        (
+            Self::from_parts(self.negative != rhs.negative, q),
+            Self::from_parts(self.negative, r),
+        )
+    }
+
+    // Greatest common divisor, always non-negative
+    pub fn gcd(&self, rhs: &BigInt) -> BigInt {
+        let (mut a, mut b) = (self.abs(), rhs.abs());
+        while !b.is_zero() {
+            let (_, r) = a.div_rem(&b);
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    // Approximate value as mantissa * 2^exponent. The top three limbs hold at least 65
+    // significant bits, more than an f64 keeps, so the mantissa is good to a few ulps.
+    pub fn to_f64_parts(&self) -> (f64, i32) {
+        let skip = self.magnitude.len().saturating_sub(3);
+        let mantissa = self.magnitude[skip..]
+            .iter()
+            .rev()
+            .fold(0., |acc, &limb| acc * 4294967296. + limb as f64);
+        let mantissa = if self.negative { -mantissa } else { mantissa };
+        (mantissa, 32 * skip as i32)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> Self {
+        let m = n.unsigned_abs();
+        BigInt::from_parts(n < 0, vec![m as u32, (m >> 32) as u32])
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => cmp_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Peel off base 10^9 digits from the bottom
+        let billion = [1_000_000_000u32];
+        let mut digits = Vec::new();
+        let mut rest = self.magnitude.clone();
+        while !rest.is_empty() {
+            let (q, r) = divrem_magnitude(&rest, &billion);
+            digits.push(r.first().copied().unwrap_or(0));
+            rest = q;
+        }
+
+        let mut s = String::new();
+        if self.negative {
+            s.push('-');
+        }
+        s.push_str(&digits.pop().unwrap().to_string());
+        for d in digits.iter().rev() {
+            s.push_str(&format!("{:09}", d));
+        }
+        write!(f, "{}", s)
+    }
+}
+
+// Negation: -&BigInt -> BigInt
+impl Neg for &BigInt {
+    type Output = BigInt;
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+// Addition: &BigInt + &BigInt -> BigInt
+impl Add for &BigInt {
+    type Output = BigInt;
+    fn add(self, rhs: &BigInt) -> BigInt {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(
+                self.negative,
+                add_magnitude(&self.magnitude, &rhs.magnitude),
+            );
+        }
+        // Opposite signs: subtract the smaller magnitude from the larger
+        match cmp_magnitude(&self.magnitude, &rhs.magnitude) {
+            Ordering::Less => {
+                BigInt::from_parts(rhs.negative, sub_magnitude(&rhs.magnitude, &self.magnitude))
+            }
+            _ => BigInt::from_parts(
+                self.negative,
+                sub_magnitude(&self.magnitude, &rhs.magnitude),
+            ),
+        }
+    }
+}
+
+// Subtraction: &BigInt - &BigInt -> BigInt
+impl Sub for &BigInt {
+    type Output = BigInt;
+    fn sub(self, rhs: &BigInt) -> BigInt {
+        self + &(-rhs)
+    }
+}
+
+// Multiplication: &BigInt * &BigInt -> BigInt
+impl Mul for &BigInt {
+    type Output = BigInt;
+    fn mul(self, rhs: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != rhs.negative,
+            mul_magnitude(&self.magnitude, &rhs.magnitude),
+        )
+    }
+}
+
+// Division: &BigInt / &BigInt -> BigInt, rounding toward zero
+impl Div for &BigInt {
+    type Output = BigInt;
+    fn div(self, rhs: &BigInt) -> BigInt {
+        self.div_rem(rhs).0
+    }
+}
+
+// Owned operands forward to the reference implementations
+macro_rules! forward_owned {
+    ($t:ty, $($trait:ident $method:ident),*) => {
+        $(
+            impl $trait for $t {
+                type Output = $t;
+                fn $method(self, rhs: $t) -> $t {
+                    (&self).$method(&rhs)
+                }
+            }
+        )*
+
+        impl Neg for $t {
+            type Output = $t;
+            fn neg(self) -> $t {
+                -&self
+            }
         }
     };
 }
+pub(crate) use forward_owned;
+
+forward_owned!(BigInt, Add add, Sub sub, Mul mul, Div div);
+
+#[cfg(test)]
+mod integer_tests {
+    use crate::math::integer::BigInt;
 
-trait_alias!(Integer =
-    'static +
-    Display +
-    Copy +
-    Sized +
-    PartialOrd +
-    Into<usize> +
-    From<usize> +
-    Mul<Output = Self> + MulAssign +
-);
+    #[test]
+    fn arithmetic() {
+        let a = BigInt::from(i64::MAX);
+        let b = BigInt::from(-12345);
+
+        // (2^63 - 1)^2 overflows every primitive type
+        let square = &a * &a;
+        assert_eq!(square.to_string(), "85070591730234615847396907784232501249");
+        assert_eq!(&square / &a, a);
+        assert_eq!((&a + &b).to_string(), "9223372036854763462");
+        assert_eq!((&b - &a).to_string(), "-9223372036854788152");
+        assert_eq!((&a - &a), BigInt::zero());
+        assert!(b < BigInt::zero() && BigInt::zero() < a);
+
+        let (q, r) = BigInt::from(-7).div_rem(&BigInt::from(2));
+        assert_eq!((q, r), (BigInt::from(-3), BigInt::from(-1)));
+        assert_eq!(BigInt::from(84).gcd(&BigInt::from(-36)), BigInt::from(12));
+        assert_eq!(BigInt::pow2(70).to_string(), "1180591620717411303424");
+
+        // The low limb of 2^64 + 2^20 still counts towards the f64 approximation
+        let (m, e) = (&BigInt::pow2(64) + &BigInt::pow2(20)).to_f64_parts();
+        assert_eq!(m * 2_f64.powi(e), 2_f64.powi(64) + 2_f64.powi(20));
+    }
+}
@@ -0,0 +1,449 @@
+use super::{bezier3::Bezier3, solvers::newton2, solvers::solve_cubic, vec2::Vec2};
+
+// Depth limit for curve-curve intersection, counted per clip or split
+const MAX_DEPTH: usize = 64;
+
+// Largest fraction of a curve's parameter range a clip may keep before the curves are
+// split instead, since clipping converges slowly when it removes little
+const MAX_CLIP: f32 = 0.8;
+
+// Number of interior samples used to confirm that two curves overlap
+const OVERLAP_SAMPLES: usize = 8;
+
+// A pair of sub-curves whose hulls still overlap once clipping has shrunk both to tol,
+// along with the parameter ranges they cover on the original curves
+struct Candidate {
+    a_range: (f32, f32),
+    b_range: (f32, f32),
+}
+
+// Find the parameter pairs (s, t) where a(s) and b(t) coincide to within tol.
+// Tangential contacts are reported once. If the curves overlap over a range, the
+// ends of the shared range are returned instead of a continuum of points.
+pub fn curve_curve(a: &Bezier3, b: &Bezier3, tol: f32) -> Vec<(f32, f32)> {
+    if let Some((start, end)) = curve_overlap(a, b, tol) {
+        return vec![start, end];
+    }
+
+    let mut candidates = Vec::new();
+    clip(a, (0., 1.), b, (0., 1.), tol, 0, false, &mut candidates);
+
+    // Neighbouring leaves belong to the same intersection; this is what merges the
+    // long runs of candidates produced along a tangential contact
+    candidates.sort_by(|x, y| x.a_range.0.total_cmp(&y.a_range.0));
+    let mut clusters: Vec<Candidate> = Vec::new();
+    for c in candidates {
+        let touches = |r: (f32, f32), s: (f32, f32)| r.0 <= s.1 + 1e-6 && s.0 <= r.1 + 1e-6;
+        let existing = clusters
+            .iter_mut()
+            .find(|k| touches(k.a_range, c.a_range) && touches(k.b_range, c.b_range));
+        if let Some(k) = existing {
+            k.a_range = (k.a_range.0.min(c.a_range.0), k.a_range.1.max(c.a_range.1));
+            k.b_range = (k.b_range.0.min(c.b_range.0), k.b_range.1.max(c.b_range.1));
+        } else {
+            clusters.push(c);
+        }
+    }
+
+    let mut result: Vec<(f32, f32)> = clusters
+        .iter()
+        .map(|k| closest_in_ranges(a, b, k.a_range, k.b_range))
+        .map(|(s, t)| refine(a, b, s, t, tol))
+        .collect();
+    result.sort_by(|x, y| x.0.total_cmp(&y.0));
+    result
+}
+
+// Detect curves that share a segment. Returns the parameter pairs at either end of the
+// shared range, or None if the curves only meet at isolated points.
+pub fn curve_overlap(a: &Bezier3, b: &Bezier3, tol: f32) -> Option<((f32, f32), (f32, f32))> {
+    // Any overlap must begin and end at an endpoint of one of the two curves
+    let mut pairs: Vec<(f32, f32)> = Vec::new();
+    for s in [0., 1.] {
+        let (t, d) = b.nearest_point(a.get_point(s));
+        if d <= tol {
+            pairs.push((s, t));
+        }
+    }
+    for t in [0., 1.] {
+        let (s, d) = a.nearest_point(b.get_point(t));
+        if d <= tol {
+            pairs.push((s, t));
+        }
+    }
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    pairs.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let (start, end) = (pairs[0], pairs[pairs.len() - 1]);
+    if (a.get_point(start.0) - a.get_point(end.0)).mag() <= tol {
+        return None;
+    }
+
+    // Confirm that the curves stay together between the two ends
+    for i in 1..OVERLAP_SAMPLES {
+        let f = i as f32 / OVERLAP_SAMPLES as f32;
+        let s = start.0 + f * (end.0 - start.0);
+        let (t, d) = b.nearest_point(a.get_point(s));
+        let (t_min, t_max) = (start.1.min(end.1), start.1.max(end.1));
+        if d > tol || t < t_min - tol || t > t_max + tol {
+            return None;
+        }
+    }
+
+    Some((start, end))
+}
+
+// Find the parameter pairs (t, s) where curve(t) = origin + s * direction
+pub fn curve_line(curve: &Bezier3, origin: Vec2<f32>, direction: Vec2<f32>) -> Vec<(f32, f32)> {
+    let len2 = direction.dot(direction);
+    if len2 == 0. {
+        return Vec::new();
+    }
+    let to_line = |t: f32| (curve.get_point(t) - origin).dot(direction) / len2;
+
+    // Signed distances of the control points from the line (scaled by |direction|)
+    let d: Vec<f32> = curve
+        .control_points
+        .iter()
+        .map(|p| direction.cross(*p - origin))
+        .collect();
+
+    // A curve lying along the line meets it everywhere; report its endpoints
+    let eps = 1e-6
+        * len2.sqrt()
+        * curve
+            .control_points
+            .iter()
+            .map(|p| p.mag())
+            .fold(1., f32::max);
+    if d.iter().all(|d_i| d_i.abs() <= eps) {
+        return vec![(0., to_line(0.)), (1., to_line(1.))];
+    }
+
+    // Convert the Bernstein coefficients to the power basis and solve for the zeros
+    let c_3 = -d[0] + 3. * d[1] - 3. * d[2] + d[3];
+    let c_2 = 3. * d[0] - 6. * d[1] + 3. * d[2];
+    let c_1 = -3. * d[0] + 3. * d[1];
+    let c_0 = d[0];
+
+    let mut result: Vec<(f32, f32)> = Vec::new();
+    for t in solve_cubic(c_3, c_2, c_1, c_0) {
+        if !(-1e-5..=1. + 1e-5).contains(&t) {
+            continue;
+        }
+        let t = t.clamp(0., 1.);
+        if result.iter().any(|r| (r.0 - t).abs() <= 1e-6) {
+            continue;
+        }
+        result.push((t, to_line(t)));
+    }
+    result
+}
+
+// Find the parameter pairs (t, s) where curve(t) = origin + s * direction for s >= 0
+pub fn curve_ray(curve: &Bezier3, origin: Vec2<f32>, direction: Vec2<f32>) -> Vec<(f32, f32)> {
+    curve_line(curve, origin, direction)
+        .into_iter()
+        .filter(|(_, s)| *s >= 0.)
+        .collect()
+}
+
+fn hulls_overlap(a: &Bezier3, b: &Bezier3, tol: f32) -> bool {
+    let h_a = a.hull();
+    let h_b = b.hull();
+    h_a[0] <= h_b[2] + tol
+        && h_b[0] <= h_a[2] + tol
+        && h_a[1] <= h_b[3] + tol
+        && h_b[1] <= h_a[3] + tol
+}
+
+fn hull_size(curve: &Bezier3) -> f32 {
+    let h = curve.hull();
+    (h[2] - h[0]).max(h[3] - h[1])
+}
+
+// The fat line of a curve: a point, a unit normal and the band of signed distances
+// [d_min, d_max] along it that contains the whole curve. None if the curve is a point.
+fn fat_line(curve: &Bezier3) -> Option<(Vec2<f32>, Vec2<f32>, f32, f32)> {
+    let p = &curve.control_points;
+    let distance = |q: Vec2<f32>, normal: Vec2<f32>| (q - p[0]).dot(normal);
+
+    if let Some(normal) = (p[3] - p[0]).normal().try_normalize() {
+        // Sederberg and Nishita's bounds for the band about the chord
+        let (d_1, d_2) = (distance(p[1], normal), distance(p[2], normal));
+        let factor = if d_1 * d_2 > 0. { 3. / 4. } else { 4. / 9. };
+        return Some((
+            p[0],
+            normal,
+            factor * d_1.min(d_2).min(0.),
+            factor * d_1.max(d_2).max(0.),
+        ));
+    }
+
+    // A closed control polygon has no chord, so bound every control point instead
+    let normal = [p[1], p[2]]
+        .iter()
+        .find_map(|q| (*q - p[0]).normal().try_normalize())?;
+    let d: Vec<f32> = p.iter().map(|q| distance(*q, normal)).collect();
+    Some((
+        p[0],
+        normal,
+        d.iter().copied().fold(f32::MAX, f32::min),
+        d.iter().copied().fold(f32::MIN, f32::max),
+    ))
+}
+
+// The parameter range of a that can lie within the fat line of b, widened by half of tol,
+// or None if a misses it. The distances of a's control points from the line, placed at
+// t = 0, 1/3, 2/3, 1, span a convex hull that encloses a's distance function, so the
+// range is bounded by where the edges between those points cross the band.
+fn clip_range(a: &Bezier3, b: &Bezier3, tol: f32) -> Option<(f32, f32)> {
+    let (origin, normal, d_min, d_max) = match fat_line(b) {
+        Some(line) => line,
+        None => return Some((0., 1.)),
+    };
+    let (d_min, d_max) = (d_min - 0.5 * tol, d_max + 0.5 * tol);
+    let e: Vec<(f32, f32)> = a
+        .control_points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i as f32 / 3., (*p - origin).dot(normal)))
+        .collect();
+
+    let mut range: Option<(f32, f32)> = None;
+    for i in 0..4 {
+        for j in i + 1..4 {
+            let ((t_i, e_i), (t_j, e_j)) = (e[i], e[j]);
+            // The fraction of the edge from point i to point j that lies inside the band
+            let (lo, hi) = if e_i == e_j {
+                if (d_min..=d_max).contains(&e_i) {
+                    (0., 1.)
+                } else {
+                    continue;
+                }
+            } else {
+                let u_min = (d_min - e_i) / (e_j - e_i);
+                let u_max = (d_max - e_i) / (e_j - e_i);
+                (u_min.min(u_max).max(0.), u_min.max(u_max).min(1.))
+            };
+            if lo > hi {
+                continue;
+            }
+            let (t_lo, t_hi) = (t_i + lo * (t_j - t_i), t_i + hi * (t_j - t_i));
+            range = Some(match range {
+                Some((r_lo, r_hi)) => (r_lo.min(t_lo), r_hi.max(t_hi)),
+                None => (t_lo, t_hi),
+            });
+        }
+    }
+    range
+}
+
+// Bezier clipping: repeatedly trim a to the part that can lie within the fat line of b,
+// then swap the roles of the curves. Where a clip removes too little, as happens with
+// several intersections or a tangential contact, the larger curve is split in half
+// instead. Candidate ranges are recorded in the caller's order, undoing any swap.
+#[allow(clippy::too_many_arguments)]
+fn clip(
+    a: &Bezier3,
+    a_range: (f32, f32),
+    b: &Bezier3,
+    b_range: (f32, f32),
+    tol: f32,
+    depth: usize,
+    swapped: bool,
+    candidates: &mut Vec<Candidate>,
+) {
+    if !hulls_overlap(a, b, tol) {
+        return;
+    }
+
+    if depth >= MAX_DEPTH || (hull_size(a) <= tol && hull_size(b) <= tol) {
+        candidates.push(if swapped {
+            Candidate {
+                a_range: b_range,
+                b_range: a_range,
+            }
+        } else {
+            Candidate { a_range, b_range }
+        });
+        return;
+    }
+
+    let (t_0, t_1) = match clip_range(a, b, tol) {
+        Some(range) => range,
+        None => return,
+    };
+    let a = a.subsegment(t_0, t_1);
+    let width = a_range.1 - a_range.0;
+    let a_range = (a_range.0 + t_0 * width, a_range.0 + t_1 * width);
+
+    if t_1 - t_0 <= MAX_CLIP {
+        clip(
+            b,
+            b_range,
+            &a,
+            a_range,
+            tol,
+            depth + 1,
+            !swapped,
+            candidates,
+        );
+    } else if hull_size(&a) >= hull_size(b) {
+        let (left, right) = a.split(0.5);
+        let mid = 0.5 * (a_range.0 + a_range.1);
+        for (half, range) in [(left, (a_range.0, mid)), (right, (mid, a_range.1))] {
+            clip(
+                b,
+                b_range,
+                &half,
+                range,
+                tol,
+                depth + 1,
+                !swapped,
+                candidates,
+            );
+        }
+    } else {
+        let (left, right) = b.split(0.5);
+        let mid = 0.5 * (b_range.0 + b_range.1);
+        for (half, range) in [(left, (b_range.0, mid)), (right, (mid, b_range.1))] {
+            clip(
+                &half,
+                range,
+                &a,
+                a_range,
+                tol,
+                depth + 1,
+                !swapped,
+                candidates,
+            );
+        }
+    }
+}
+
+// Pick the closest pair of sampled points within a cluster's parameter ranges
+fn closest_in_ranges(
+    a: &Bezier3,
+    b: &Bezier3,
+    a_range: (f32, f32),
+    b_range: (f32, f32),
+) -> (f32, f32) {
+    let samples = 16;
+    let mut best = (a_range.0, b_range.0);
+    let mut d_best = f32::MAX;
+    for i in 0..samples + 1 {
+        let s = a_range.0 + (a_range.1 - a_range.0) * i as f32 / samples as f32;
+        let p = a.get_point(s);
+        for j in 0..samples + 1 {
+            let t = b_range.0 + (b_range.1 - b_range.0) * j as f32 / samples as f32;
+            let d = (p - b.get_point(t)).mag();
+            if d < d_best {
+                best = (s, t);
+                d_best = d;
+            }
+        }
+    }
+    best
+}
+
+// Polish an intersection with Newton's method on a(s) - b(t) = 0. Near tangential
+// contacts the system is singular, so the estimate is only kept if it improves.
+fn refine(a: &Bezier3, b: &Bezier3, s: f32, t: f32, tol: f32) -> (f32, f32) {
+    let f = |s: f32, t: f32| a.get_point(s).x - b.get_point(t).x;
+    let dfs = |s: f32, _t: f32| a.first_derivative(s).x;
+    let dft = |_s: f32, t: f32| -b.first_derivative(t).x;
+    let g = |s: f32, t: f32| a.get_point(s).y - b.get_point(t).y;
+    let dgs = |s: f32, _t: f32| a.first_derivative(s).y;
+    let dgt = |_s: f32, t: f32| -b.first_derivative(t).y;
+
+    let (s_n, t_n) = newton2(s, t, f, dfs, dft, g, dgs, dgt, 1e-7);
+
+    let d = (a.get_point(s) - b.get_point(t)).mag();
+    let in_range = (0. ..=1.).contains(&s_n) && (0. ..=1.).contains(&t_n);
+    let close = (s_n - s).abs() <= tol.sqrt() && (t_n - t).abs() <= tol.sqrt();
+    if in_range && close && (a.get_point(s_n) - b.get_point(t_n)).mag() <= d {
+        (s_n, t_n)
+    } else {
+        (s, t)
+    }
+}
+
+#[cfg(test)]
+mod intersection_tests {
+    use crate::math::bezier3::Bezier3;
+    use crate::math::intersection::*;
+    use crate::math::vec2::Vec2;
+
+    fn arch() -> Bezier3 {
+        Bezier3::new(vec![
+            Vec2::new(0., 0.),
+            Vec2::new(0., 1.),
+            Vec2::new(1., 1.),
+            Vec2::new(1., 0.),
+        ])
+    }
+
+    #[test]
+    fn crossing() {
+        let a = Bezier3::line(Vec2::new(0., 0.), Vec2::new(1., 1.));
+        let b = Bezier3::line(Vec2::new(0., 1.), Vec2::new(1., 0.));
+        let hits = curve_curve(&a, &b, 1e-5);
+        assert_eq!(hits.len(), 1);
+        assert!((hits[0].0 - 0.5).abs() < 1e-3);
+        assert!((hits[0].1 - 0.5).abs() < 1e-3);
+
+        // The arch crosses a horizontal line twice
+        let horizontal = Bezier3::line(Vec2::new(-1., 0.5), Vec2::new(2., 0.5));
+        let hits = curve_curve(&arch(), &horizontal, 1e-5);
+        assert_eq!(hits.len(), 2);
+        for (s, t) in hits {
+            assert!((arch().get_point(s) - horizontal.get_point(t)).mag() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn tangential() {
+        // The arch peaks at y = 0.75 when t = 0.5
+        let peak = Bezier3::line(Vec2::new(-1., 0.75), Vec2::new(2., 0.75));
+        let hits = curve_curve(&arch(), &peak, 1e-4);
+        assert_eq!(hits.len(), 1);
+        assert!((hits[0].0 - 0.5).abs() < 1e-3);
+        assert!((hits[0].1 - 0.5).abs() < 1e-3);
+
+        // Just below the peak the line crosses twice, 0.0577 either side of it
+        let below = Bezier3::line(Vec2::new(-1., 0.74), Vec2::new(2., 0.74));
+        let hits = curve_curve(&arch(), &below, 1e-5);
+        assert_eq!(hits.len(), 2);
+        assert!((hits[0].0 - 0.4423).abs() < 1e-3);
+        assert!((hits[1].0 - 0.5577).abs() < 1e-3);
+    }
+
+    #[test]
+    fn overlapping() {
+        let a = arch();
+        let b = a.subsegment(0.25, 0.75);
+        let hits = curve_curve(&a, &b, 1e-4);
+        assert_eq!(hits.len(), 2);
+        assert!((hits[0].0 - 0.25).abs() < 1e-2 && hits[0].1.abs() < 1e-2);
+        assert!((hits[1].0 - 0.75).abs() < 1e-2 && (hits[1].1 - 1.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn lines_and_rays() {
+        let hits = curve_line(&arch(), Vec2::new(0., 0.5), Vec2::new(1., 0.));
+        assert_eq!(hits.len(), 2);
+        for (t, s) in &hits {
+            let p = arch().get_point(*t);
+            assert!((p.y - 0.5).abs() < 1e-4);
+            assert!((p.x - s).abs() < 1e-4);
+        }
+
+        // Only the crossing to the right of the origin lies on the ray
+        let hits = curve_ray(&arch(), Vec2::new(0.5, 0.5), Vec2::new(1., 0.));
+        assert_eq!(hits.len(), 1);
+        assert!(arch().get_point(hits[0].0).x > 0.5);
+    }
+}
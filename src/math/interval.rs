@@ -0,0 +1,243 @@
+use crate::math::real::Real;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::*;
+
+// Endpoint types that can be rounded outward by one unit in the last place
+pub trait Round {
+    fn round_up(self) -> Self;
+    fn round_down(self) -> Self;
+}
+
+impl Round for f32 {
+    fn round_up(self) -> f32 {
+        self.next_up()
+    }
+
+    fn round_down(self) -> f32 {
+        self.next_down()
+    }
+}
+
+// Closed interval [lo, hi]. Every operation rounds its result outward, so the interval
+// computed for an expression always contains the exact value of that expression for any
+// choice of arguments within the input intervals.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Interval<T> {
+    pub lo: T,
+    pub hi: T,
+}
+
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+impl<T: Real + Round> Interval<T> {
+    pub fn new(lo: T, hi: T) -> Self {
+        Self { lo, hi }
+    }
+
+    // The degenerate interval [x, x]
+    pub fn point(x: T) -> Self {
+        Self::new(x, x)
+    }
+
+    pub fn entire() -> Self {
+        Self::new(T::from(f32::NEG_INFINITY), T::from(f32::INFINITY))
+    }
+
+    fn rounded(lo: T, hi: T) -> Self {
+        Self::new(lo.round_down(), hi.round_up())
+    }
+
+    pub fn width(&self) -> T {
+        self.hi - self.lo
+    }
+
+    pub fn midpoint(&self) -> T {
+        (self.lo + self.hi) / T::from(2.)
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    pub fn intersects(&self, other: &Interval<T>) -> bool {
+        self.lo <= other.hi && other.lo <= self.hi
+    }
+
+    // Smallest interval containing both
+    pub fn hull(&self, other: &Interval<T>) -> Self {
+        Self::new(min(self.lo, other.lo), max(self.hi, other.hi))
+    }
+
+    // Halves of the interval, split at the midpoint
+    pub fn split(&self) -> (Self, Self) {
+        let mid = self.midpoint();
+        (Self::new(self.lo, mid), Self::new(mid, self.hi))
+    }
+
+    // x^2, which unlike x * x never dips below zero
+    pub fn square(&self) -> Self {
+        let zero = T::from(0.);
+        let (a, b) = (self.lo * self.lo, self.hi * self.hi);
+        if self.contains(zero) {
+            Self::new(zero, max(a, b).round_up())
+        } else {
+            Self::rounded(min(a, b), max(a, b))
+        }
+    }
+}
+
+impl<T: Real> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+// Conversions required by Real: a float becomes a degenerate interval, and an interval
+// converts back to its midpoint
+impl<T: Real + Round> From<f32> for Interval<T> {
+    fn from(x: f32) -> Self {
+        Interval::point(T::from(x))
+    }
+}
+
+impl<T: Real + Round> From<Interval<T>> for f32 {
+    fn from(x: Interval<T>) -> f32 {
+        x.midpoint().into()
+    }
+}
+
+// One interval is less than another only if it lies entirely below it. Overlapping
+// intervals are unordered.
+impl<T: Real + Round> PartialOrd for Interval<T> {
+    fn partial_cmp(&self, other: &Interval<T>) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.hi < other.lo {
+            Some(Ordering::Less)
+        } else if self.lo > other.hi {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
+// Negation: -Interval<T> -> Interval<T>, which is exact
+impl<T: Real + Round> Neg for Interval<T> {
+    type Output = Interval<T>;
+    fn neg(self) -> Interval<T> {
+        Interval::new(-self.hi, -self.lo)
+    }
+}
+
+// Addition: Interval<T> + Interval<T> -> Interval<T>
+impl<T: Real + Round> Add for Interval<T> {
+    type Output = Interval<T>;
+    fn add(self, rhs: Interval<T>) -> Interval<T> {
+        Interval::rounded(self.lo + rhs.lo, self.hi + rhs.hi)
+    }
+}
+
+// Subtraction: Interval<T> - Interval<T> -> Interval<T>
+impl<T: Real + Round> Sub for Interval<T> {
+    type Output = Interval<T>;
+    fn sub(self, rhs: Interval<T>) -> Interval<T> {
+        Interval::rounded(self.lo - rhs.hi, self.hi - rhs.lo)
+    }
+}
+
+// Multiplication: Interval<T> * Interval<T> -> Interval<T>, spanning all endpoint products
+impl<T: Real + Round> Mul for Interval<T> {
+    type Output = Interval<T>;
+    fn mul(self, rhs: Interval<T>) -> Interval<T> {
+        let products = [
+            self.lo * rhs.lo,
+            self.lo * rhs.hi,
+            self.hi * rhs.lo,
+            self.hi * rhs.hi,
+        ];
+        Interval::rounded(
+            products.into_iter().reduce(min).unwrap(),
+            products.into_iter().reduce(max).unwrap(),
+        )
+    }
+}
+
+// Division: Interval<T> / Interval<T> -> Interval<T>. Dividing by an interval containing
+// zero gives the whole real line.
+impl<T: Real + Round> Div for Interval<T> {
+    type Output = Interval<T>;
+    fn div(self, rhs: Interval<T>) -> Interval<T> {
+        if rhs.contains(T::from(0.)) {
+            return Interval::entire();
+        }
+        let one = T::from(1.);
+        self * Interval::rounded(one / rhs.hi, one / rhs.lo)
+    }
+}
+
+impl<T: Real + Round> AddAssign for Interval<T> {
+    fn add_assign(&mut self, rhs: Interval<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Real + Round> SubAssign for Interval<T> {
+    fn sub_assign(&mut self, rhs: Interval<T>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Real + Round> MulAssign for Interval<T> {
+    fn mul_assign(&mut self, rhs: Interval<T>) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Real + Round> DivAssign for Interval<T> {
+    fn div_assign(&mut self, rhs: Interval<T>) {
+        *self = *self / rhs;
+    }
+}
+
+#[cfg(test)]
+mod interval_tests {
+    use crate::math::interval::Interval;
+
+    #[test]
+    fn arithmetic() {
+        let a = Interval::new(1., 2.);
+        let b = Interval::new(-3., 0.5);
+
+        let sum = a + b;
+        assert!(sum.lo <= -2. && sum.hi >= 2.5 && sum.width() < 4.6);
+        let product = a * b;
+        assert!(product.contains(-6.) && product.contains(1.) && product.width() < 7.1);
+        assert_eq!((a / b), Interval::entire());
+        assert!((b / a).contains(-3.) && (b / a).contains(0.5));
+        assert!(b.square().lo == 0. && b.square().contains(9.));
+
+        // Outward rounding keeps the exact result inside
+        let third = Interval::point(1.) / Interval::point(3.);
+        assert!(third.lo < third.hi);
+        assert!((third.lo as f64) <= 1. / 3. && (third.hi as f64) >= 1. / 3.);
+
+        assert!(a > Interval::new(-1., 0.5));
+        assert_eq!(a.partial_cmp(&Interval::new(1.5, 3.)), None);
+    }
+}
@@ -2,7 +2,7 @@ use crate::math::real::Real;
 use crate::Vec2;
 use std::ops::Mul;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Matrix2x2<T> {
     pub a: T,
     pub b: T,
@@ -41,19 +41,150 @@ impl<T: Real> Matrix2x2<T> {
         scale_matrix * *self
     }
 
+    pub fn scale_xy(&self, scale_x: f32, scale_y: f32) -> Self {
+        let scale_matrix = Matrix2x2 {
+            a: T::from(scale_x),
+            b: T::from(0.0),
+            c: T::from(0.0),
+            d: T::from(scale_y),
+        };
+
+        scale_matrix * *self
+    }
+
+    // Shear by the angles theta_x (of the y-axis toward x) and theta_y (of the x-axis toward y)
+    pub fn skew(&self, theta_x: f32, theta_y: f32) -> Self {
+        let skew_matrix = Matrix2x2 {
+            a: T::from(1.0),
+            b: T::from(theta_x.tan()),
+            c: T::from(theta_y.tan()),
+            d: T::from(1.0),
+        };
+
+        skew_matrix * *self
+    }
+
     pub fn determinant(&self) -> T {
-        T::from(1.0) / (self.a * self.d - self.b * self.c)
+        self.a * self.d - self.b * self.c
     }
 
     pub fn inverse(&self) -> Self {
-        let det = self.determinant();
+        let inv_det = T::from(1.0) / self.determinant();
         Self {
-            a: det * self.d,
-            b: det * (T::from(0.0) - self.b),
-            c: det * (T::from(0.0) - self.c),
-            d: det * (self.a),
+            a: inv_det * self.d,
+            b: inv_det * (T::from(0.0) - self.b),
+            c: inv_det * (T::from(0.0) - self.c),
+            d: inv_det * (self.a),
         }
     }
+
+    pub fn transpose(&self) -> Self {
+        Self {
+            a: self.a,
+            b: self.c,
+            c: self.b,
+            d: self.d,
+        }
+    }
+
+    pub fn trace(&self) -> T {
+        self.a + self.d
+    }
+}
+
+// A linear map split as rotation * shear * scale, angles in radians. The shear moves x by
+// tan(skew) times y. Negative scale_y means the map includes a reflection.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Decomposition {
+    pub rotation: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub skew: f32,
+}
+
+impl Matrix2x2<f32> {
+    // Real eigenvalues in ascending order, or None if they are a complex pair
+    pub fn eigenvalues(&self) -> Option<(f32, f32)> {
+        let half_trace = self.trace() / 2.;
+        let discriminant = half_trace * half_trace - self.determinant();
+        if discriminant < 0. {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        Some((half_trace - root, half_trace + root))
+    }
+
+    // Unit eigenvectors matching the eigenvalues, or None if those are complex
+    pub fn eigenvectors(&self) -> Option<[Vec2<f32>; 2]> {
+        let (l_0, l_1) = self.eigenvalues()?;
+        let vector = |l: f32, fallback: Vec2<f32>| {
+            // Each row of (M - l I) is orthogonal to the eigenvector; use the larger one
+            let (r_0, r_1) = (Vec2::new(self.a - l, self.b), Vec2::new(self.c, self.d - l));
+            let row = if r_0.mag() >= r_1.mag() { r_0 } else { r_1 };
+            Vec2::new(-row.y, row.x).try_normalize().unwrap_or(fallback)
+        };
+        Some([
+            vector(l_0, Vec2::new(1., 0.)),
+            vector(l_1, Vec2::new(0., 1.)),
+        ])
+    }
+
+    // Singular value decomposition M = U * diag(s_0, s_1) * V^T with s_0 >= s_1 >= 0. U is
+    // a rotation; V^T holds any reflection.
+    pub fn svd(&self) -> (Matrix2x2<f32>, (f32, f32), Matrix2x2<f32>) {
+        let e = (self.a + self.d) / 2.;
+        let f = (self.a - self.d) / 2.;
+        let g = (self.c + self.b) / 2.;
+        let h = (self.c - self.b) / 2.;
+        let q = (e * e + h * h).sqrt();
+        let r = (f * f + g * g).sqrt();
+        let (a_1, a_2) = (g.atan2(f), h.atan2(e));
+        let theta = (a_2 - a_1) / 2.;
+        let phi = (a_2 + a_1) / 2.;
+
+        let u = Matrix2x2::<f32>::identity().rotation(phi);
+        let mut v_t = Matrix2x2::<f32>::identity().rotation(theta);
+        let mut s_1 = q - r;
+        if s_1 < 0. {
+            s_1 = -s_1;
+            v_t.c = -v_t.c;
+            v_t.d = -v_t.d;
+        }
+        (u, (q + r, s_1), v_t)
+    }
+
+    // Polar decomposition M = R * S with R orthogonal and S symmetric positive semidefinite
+    pub fn polar(&self) -> (Matrix2x2<f32>, Matrix2x2<f32>) {
+        let (u, (s_0, s_1), v_t) = self.svd();
+        let sigma = Matrix2x2 {
+            a: s_0,
+            b: 0.,
+            c: 0.,
+            d: s_1,
+        };
+        (u * v_t, v_t.transpose() * sigma * v_t)
+    }
+
+    // None if the map is singular, collapsing the plane onto a line or a point. Its skew
+    // would then be undefined, as the shear is measured relative to scale_y.
+    pub fn decompose(&self) -> Option<Decomposition> {
+        let rotation = self.c.atan2(self.a);
+        let scale_x = (self.a * self.a + self.c * self.c).sqrt();
+
+        // Undo the rotation to leave the upper triangular shear * scale
+        let (sin, cos) = rotation.sin_cos();
+        let shear = cos * self.b + sin * self.d;
+        let scale_y = cos * self.d - sin * self.b;
+        if scale_x == 0. || scale_y.abs() <= 1e-6 * scale_x.max(shear.abs()) {
+            return None;
+        }
+        Some(Decomposition {
+            rotation,
+            scale_x,
+            scale_y,
+            skew: (shear / scale_y).atan(),
+        })
+    }
 }
 
 impl<T> Mul<Vec2<T>> for Matrix2x2<T>
@@ -85,3 +216,88 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod matrix2x2_tests {
+    use crate::math::matrix2x2::Matrix2x2;
+    use crate::math::vec2::Vec2;
+
+    fn approx_eq(m: Matrix2x2<f32>, n: Matrix2x2<f32>) -> bool {
+        [m.a - n.a, m.b - n.b, m.c - n.c, m.d - n.d]
+            .iter()
+            .all(|x| x.abs() < 1e-5)
+    }
+
+    #[test]
+    fn basics() {
+        let m = Matrix2x2 {
+            a: 2.,
+            b: 1.,
+            c: 1.,
+            d: 3.,
+        };
+        assert_eq!(m.determinant(), 5.);
+        assert_eq!(m.trace(), 5.);
+        assert!(approx_eq(m * m.inverse(), Matrix2x2::identity()));
+        assert_eq!(m.transpose().b, 1.);
+
+        // Symmetric, so the eigenvectors are orthogonal
+        let (l_0, l_1) = m.eigenvalues().unwrap();
+        let [v_0, v_1] = m.eigenvectors().unwrap();
+        assert!((m * v_0).approx_eq(v_0 * l_0, 1e-5));
+        assert!((m * v_1).approx_eq(v_1 * l_1, 1e-5));
+        assert!(v_0.dot(v_1).abs() < 1e-6);
+
+        // A rotation has no real eigenvalues
+        assert!(Matrix2x2::<f32>::identity()
+            .rotation(1.)
+            .eigenvalues()
+            .is_none());
+    }
+
+    #[test]
+    fn decompositions() {
+        let m = Matrix2x2::identity()
+            .scale_xy(2., -0.5)
+            .skew(0.3, 0.)
+            .rotation(0.4);
+        let (u, (s_0, s_1), v_t) = m.svd();
+        let sigma = Matrix2x2 {
+            a: s_0,
+            b: 0.,
+            c: 0.,
+            d: s_1,
+        };
+        assert!(s_0 >= s_1 && s_1 >= 0.);
+        assert!(approx_eq(u * sigma * v_t, m));
+
+        let (r, s) = m.polar();
+        assert!(approx_eq(r * s, m));
+        assert!(approx_eq(r.transpose() * r, Matrix2x2::identity()));
+        assert!((s.b - s.c).abs() < 1e-6);
+
+        // Rebuild a map from its rotation, shear and scale
+        let rotation = Matrix2x2::identity().rotation(0.7);
+        let shear_scale = Matrix2x2 {
+            a: 1.5,
+            b: 0.2_f32.tan() * 0.8,
+            c: 0.,
+            d: 0.8,
+        };
+        let parts = (rotation * shear_scale).decompose().unwrap();
+        assert!((parts.rotation - 0.7).abs() < 1e-5);
+        assert!((parts.scale_x - 1.5).abs() < 1e-5 && (parts.scale_y - 0.8).abs() < 1e-5);
+        assert!((parts.skew - 0.2).abs() < 1e-5);
+        assert!((m * Vec2::new(0., 0.)).approx_eq(Vec2::new(0., 0.), 0.));
+
+        // A map onto a line, with parallel columns, has no skew to report
+        let flat = Matrix2x2 {
+            a: 1.,
+            b: 2.,
+            c: 2.,
+            d: 4.,
+        };
+        assert_eq!(flat.decompose(), None);
+        assert_eq!(Matrix2x2::identity().scale(0.).decompose(), None);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::math::real::Real;
+use crate::Vec2;
+use std::ops::Mul;
+
+// Row-major 4x4 matrix
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Matrix4x4<T> {
+    pub m: [[T; 4]; 4],
+}
+
+impl<T: Real> Matrix4x4<T> {
+    pub fn new(m: [[T; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub fn identity() -> Self {
+        let mut m = [[T::from(0.0); 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] = T::from(1.0);
+        }
+        Self { m }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = self.m;
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, x) in row.iter_mut().enumerate() {
+                *x = self.m[j][i];
+            }
+        }
+        Self { m }
+    }
+
+    pub fn scale(&self, s: T) -> Self {
+        let mut m = self.m;
+        for x in m.iter_mut().flatten() {
+            *x *= s;
+        }
+        Self { m }
+    }
+
+    // Multiply a column of four points, treating each as a row of the right-hand matrix
+    pub fn apply(&self, g: &[Vec2<T>; 4]) -> [Vec2<T>; 4] {
+        let mut result = [Vec2::new(T::from(0.0), T::from(0.0)); 4];
+        for (r, row) in result.iter_mut().zip(self.m.iter()) {
+            for (&m_ij, &g_j) in row.iter().zip(g.iter()) {
+                *r = *r + g_j * m_ij;
+            }
+        }
+        result
+    }
+}
+
+// The basis conversions use inverses written out by hand, so only the tests that check
+// them need a general inverse
+#[cfg(test)]
+impl Matrix4x4<f32> {
+    // Inverse by Gauss-Jordan elimination with partial pivoting, or None if singular
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inv = Self::identity().m;
+        for col in 0..4 {
+            let pivot = (col..4)
+                .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+                .unwrap();
+            if a[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let p = a[col][col];
+            for j in 0..4 {
+                a[col][j] /= p;
+                inv[col][j] /= p;
+            }
+            for i in 0..4 {
+                if i != col {
+                    let f = a[i][col];
+                    for j in 0..4 {
+                        a[i][j] -= f * a[col][j];
+                        inv[i][j] -= f * inv[col][j];
+                    }
+                }
+            }
+        }
+        Some(Self { m: inv })
+    }
+}
+
+impl<T> Mul<Matrix4x4<T>> for Matrix4x4<T>
+where
+    T: Real,
+{
+    type Output = Matrix4x4<T>;
+
+    fn mul(self, rhs: Matrix4x4<T>) -> Self::Output {
+        let mut m = [[T::from(0.0); 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, x) in row.iter_mut().enumerate() {
+                for k in 0..4 {
+                    *x += self.m[i][k] * rhs.m[k][j];
+                }
+            }
+        }
+        Matrix4x4 { m }
+    }
+}
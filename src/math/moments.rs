@@ -0,0 +1,121 @@
+use super::{bezier3::Bezier3, path::Path, vec2::Vec2};
+use std::ops::{Add, AddAssign};
+
+// Area moments of the region swept between the origin and a curve. Each field is the
+// integral of its monomial over that region, so summing the moments of the segments of a
+// closed path gives the moments of the enclosed region. Counterclockwise is positive.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Moments {
+    pub area: f32,
+    pub x: f32,
+    pub y: f32,
+    pub xx: f32,
+    pub xy: f32,
+    pub yy: f32,
+}
+
+impl Moments {
+    // Moments of the region enclosed by a spline. An open spline is closed with a
+    // straight segment from its last endpoint back to its first.
+    pub fn of_spline(spline: &[Bezier3]) -> Self {
+        Path::new(spline.to_vec(), true)
+            .closed_segments()
+            .iter()
+            .fold(Moments::default(), |moments, curve| {
+                moments + curve.moments()
+            })
+    }
+
+    pub fn centroid(&self) -> Option<Vec2<f32>> {
+        if self.area == 0. {
+            None
+        } else {
+            Some(Vec2::new(self.x / self.area, self.y / self.area))
+        }
+    }
+
+    // Second moments (I_xx, I_xy, I_yy) about the centroid, via the parallel axis theorem.
+    // I_xx is about the horizontal axis, so it integrates (y - c_y)^2, and I_yy (x - c_x)^2.
+    pub fn central(&self) -> (f32, f32, f32) {
+        match self.centroid() {
+            Some(c) => (
+                self.yy - self.area * c.y * c.y,
+                self.xy - self.area * c.x * c.y,
+                self.xx - self.area * c.x * c.x,
+            ),
+            None => (self.yy, self.xy, self.xx),
+        }
+    }
+
+    // Polar moment of inertia about the centroid
+    pub fn polar_inertia(&self) -> f32 {
+        let (i_xx, _, i_yy) = self.central();
+        i_xx + i_yy
+    }
+}
+
+impl Add for Moments {
+    type Output = Moments;
+    fn add(self, rhs: Moments) -> Moments {
+        Moments {
+            area: self.area + rhs.area,
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            xx: self.xx + rhs.xx,
+            xy: self.xy + rhs.xy,
+            yy: self.yy + rhs.yy,
+        }
+    }
+}
+
+impl AddAssign for Moments {
+    fn add_assign(&mut self, rhs: Moments) {
+        *self = *self + rhs;
+    }
+}
+
+#[cfg(test)]
+mod moments_tests {
+    use crate::math::bezier3::Bezier3;
+    use crate::math::moments::Moments;
+    use crate::math::vec2::Vec2;
+
+    #[test]
+    fn rectangle() {
+        // Counterclockwise 2x1 rectangle with its corner at (1, 1)
+        let corners = [
+            Vec2::new(1., 1.),
+            Vec2::new(3., 1.),
+            Vec2::new(3., 2.),
+            Vec2::new(1., 2.),
+        ];
+        let spline: Vec<Bezier3> = (0..4)
+            .map(|i| Bezier3::line(corners[i], corners[(i + 1) % 4]))
+            .collect();
+
+        let moments = Moments::of_spline(&spline);
+        assert!((moments.area - 2.).abs() < 1e-5);
+        let c = moments.centroid().unwrap();
+        assert!((c.x - 2.).abs() < 1e-5 && (c.y - 1.5).abs() < 1e-5);
+
+        // b*h^3/12 and h*b^3/12 about the centroid, with width b = 2 and height h = 1
+        let (i_xx, i_xy, i_yy) = moments.central();
+        assert!((i_xx - 2. / 12.).abs() < 1e-4);
+        assert!(i_xy.abs() < 1e-4);
+        assert!((i_yy - 2. * 2. * 2. / 12.).abs() < 1e-4);
+
+        // Leaving out the last edge closes the path implicitly
+        assert!((Moments::of_spline(&spline[..3]).area - 2.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn closed_form_area() {
+        let curve = Bezier3::new(vec![
+            Vec2::new(0.2, 0.2),
+            Vec2::new(0.1, 0.9),
+            Vec2::new(0.4, 0.4),
+            Vec2::new(0.9, 0.8),
+        ]);
+        assert!((curve.signed_area() - curve.moments().area).abs() < 1e-6);
+    }
+}
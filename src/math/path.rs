@@ -0,0 +1,525 @@
+use super::{
+    basis::Basis, bezier3::Bezier3, intersection::curve_ray, matrix2x2::Matrix2x2,
+    moments::Moments, vec2::Vec2,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Orientation {
+    Clockwise,
+    Counterclockwise,
+}
+
+// A sequence of cubic segments where each segment starts at the end of the previous one.
+// A closed path also joins its last segment back to its first; if their endpoints don't
+// meet, the gap is bridged with a straight line.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Path {
+    pub segments: Vec<Bezier3>,
+    pub closed: bool,
+}
+
+impl Path {
+    pub fn new(segments: Vec<Bezier3>, closed: bool) -> Self {
+        Self { segments, closed }
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn start(&self) -> Option<Vec2<f32>> {
+        self.segments.first().map(|s| s.control_points[0])
+    }
+
+    pub fn end(&self) -> Option<Vec2<f32>> {
+        self.segments.last().map(|s| s.control_points[3])
+    }
+
+    // Append a segment, moving its start point onto the current end of the path
+    pub fn push(&mut self, mut segment: Bezier3) {
+        if let Some(end) = self.end() {
+            segment.control_points[0] = end;
+        }
+        self.segments.push(segment);
+    }
+
+    // Segments before and after segment s, wrapping around on closed paths
+    pub fn neighbours(&self, s: usize) -> (Option<usize>, Option<usize>) {
+        let n = self.segments.len();
+        let previous = if s > 0 {
+            Some(s - 1)
+        } else if self.closed && n > 1 {
+            Some(n - 1)
+        } else {
+            None
+        };
+        let next = if s + 1 < n {
+            Some(s + 1)
+        } else if self.closed && n > 1 {
+            Some(0)
+        } else {
+            None
+        };
+        (previous, next)
+    }
+
+    // Move handle i of segment s, as shown in the given basis. Neighbouring segments that
+    // were joined to the moved segment's ends stay joined.
+    pub fn move_handle(&mut self, s: usize, i: usize, basis: Basis, position: Vec2<f32>) {
+        let start = self.segments[s].control_points[0];
+        let end = self.segments[s].control_points[3];
+        self.segments[s].set_control_handle(basis, i, position);
+        let new_start = self.segments[s].control_points[0];
+        let new_end = self.segments[s].control_points[3];
+
+        let (previous, next) = self.neighbours(s);
+        if let Some(p) = previous {
+            if self.segments[p].control_points[3] == start {
+                self.segments[p].control_points[3] = new_start;
+            }
+        }
+        if let Some(q) = next {
+            if self.segments[q].control_points[0] == end {
+                self.segments[q].control_points[0] = new_end;
+            }
+        }
+    }
+
+    // Append a segment from the end of the path to a point, leaving smoothly along the
+    // previous segment's end tangent. Nothing is added if the point is the end itself.
+    pub fn append_segment(&mut self, to: Vec2<f32>) {
+        let (from, last) = match (self.end(), self.segments.last()) {
+            (Some(from), Some(last)) => (from, last),
+            _ => return,
+        };
+        // A segment of zero length would have all four control points coincident
+        let chord = to - from;
+        if chord.mag() == 0. {
+            return;
+        }
+        let direction = last
+            .tangent(1.)
+            .try_normalize()
+            .or_else(|| chord.try_normalize())
+            .unwrap_or(Vec2::new(1., 0.));
+        self.push(Bezier3::new(vec![
+            from,
+            from + direction * (chord.mag() / 3.),
+            to - chord / 3.,
+            to,
+        ]));
+    }
+
+    // Split segment s at parameter t, adding a node there
+    pub fn insert_point(&mut self, s: usize, t: f32) {
+        let (left, right) = self.segments[s].split(t);
+        self.segments[s] = left;
+        self.segments.insert(s + 1, right);
+    }
+
+    // Segment, parameter and distance of the point on the path nearest to p
+    pub fn nearest_point(&self, p: Vec2<f32>) -> Option<(usize, f32, f32)> {
+        self.segments
+            .iter()
+            .enumerate()
+            .map(|(s, segment)| {
+                let (t, d) = segment.nearest_point(p);
+                (s, t, d)
+            })
+            .min_by(|x, y| x.2.total_cmp(&y.2))
+    }
+
+    // Delete node k, the start of segment k (or the end of the path for k = len on an open
+    // path). An interior node's two segments are merged into one cubic fitted to both. The
+    // path always keeps at least one segment; returns whether a node was removed.
+    pub fn delete_node(&mut self, k: usize) -> bool {
+        let n = self.segments.len();
+        if n < 2 || k > n || (self.closed && k == n) {
+            return false;
+        }
+
+        if !self.closed && k == 0 {
+            self.segments.remove(0);
+        } else if !self.closed && k == n {
+            self.segments.pop();
+        } else {
+            // Merge the segments either side of the node
+            let before = (k + n - 1) % n;
+            let merged = fit_cubic(&[self.segments[before].clone(), self.segments[k].clone()]);
+            self.segments[before] = merged;
+            self.segments.remove(k);
+            if k == 0 {
+                // The merged segment wrapped around the start; rotate it to the front
+                let last = self.segments.pop().unwrap();
+                self.segments.insert(0, last);
+            }
+        }
+        true
+    }
+
+    // Close the path, adding a straight segment if its ends don't already meet
+    pub fn close(&mut self) {
+        if let (Some(start), Some(end)) = (self.start(), self.end()) {
+            if start != end {
+                self.segments.push(Bezier3::line(end, start));
+            }
+        }
+        self.closed = true;
+    }
+
+    pub fn open(&mut self) {
+        self.closed = false;
+    }
+
+    // Check that consecutive segments meet to within tol
+    pub fn is_connected(&self, tol: f32) -> bool {
+        self.segments
+            .windows(2)
+            .all(|w| (w[0].control_points[3] - w[1].control_points[0]).mag() <= tol)
+    }
+
+    // Segments of the path, with the straight line that closes it if one is needed
+    pub fn closed_segments(&self) -> Vec<Bezier3> {
+        let mut segments = self.segments.clone();
+        if let (Some(start), Some(end)) = (self.start(), self.end()) {
+            if start != end {
+                segments.push(Bezier3::line(end, start));
+            }
+        }
+        segments
+    }
+
+    // Tight bounding box [x_min, y_min, x_max, y_max] of all segments
+    pub fn bounds(&self) -> Option<[f32; 4]> {
+        self.segments.iter().map(|s| s.bounds()).reduce(|a, b| {
+            [
+                a[0].min(b[0]),
+                a[1].min(b[1]),
+                a[2].max(b[2]),
+                a[3].max(b[3]),
+            ]
+        })
+    }
+
+    // Apply the affine map p -> m * p + translation to every segment
+    pub fn transform(&self, m: Matrix2x2<f32>, translation: Vec2<f32>) -> Path {
+        Path::new(
+            self.segments
+                .iter()
+                .map(|s| s.transform(m, translation))
+                .collect(),
+            self.closed,
+        )
+    }
+
+    // Apply the linear map m about a pivot point, followed by a translation
+    pub fn transform_about(
+        &self,
+        m: Matrix2x2<f32>,
+        pivot: Vec2<f32>,
+        translation: Vec2<f32>,
+    ) -> Path {
+        self.transform(m, pivot + translation - m * pivot)
+    }
+
+    pub fn moments(&self) -> Moments {
+        Moments::of_spline(&self.segments)
+    }
+
+    // Signed enclosed area, positive for counterclockwise paths
+    pub fn signed_area(&self) -> f32 {
+        self.moments().area
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        if self.signed_area() < 0. {
+            Orientation::Clockwise
+        } else {
+            Orientation::Counterclockwise
+        }
+    }
+
+    // Reverse the direction of travel, flipping the orientation
+    pub fn reverse(&mut self) {
+        self.segments.reverse();
+        for segment in self.segments.iter_mut() {
+            segment.control_points.reverse();
+        }
+    }
+
+    pub fn reversed(&self) -> Self {
+        let mut path = self.clone();
+        path.reverse();
+        path
+    }
+
+    // Number of times the path winds counterclockwise around p. Open paths are treated as
+    // closed, as they are when filled.
+    pub fn winding_number(&self, p: Vec2<f32>) -> i32 {
+        // Cast a ray in +x and count signed crossings. Crossings at segment endpoints are
+        // seen by both neighbouring segments, so each counts for half.
+        let mut winding: f32 = 0.;
+        for segment in self.closed_segments() {
+            for (t, _) in curve_ray(&segment, p, Vec2::new(1., 0.)) {
+                let dy = segment.first_derivative(t).y;
+                let weight = if t <= 1e-6 || t >= 1. - 1e-6 { 0.5 } else { 1. };
+                if dy > 0. {
+                    winding += weight;
+                } else if dy < 0. {
+                    winding -= weight;
+                }
+            }
+        }
+        winding.round() as i32
+    }
+
+    pub fn contains(&self, p: Vec2<f32>, rule: FillRule) -> bool {
+        let winding = self.winding_number(p);
+        match rule {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+// Least squares fit of one cubic to a run of joined segments, keeping the endpoints and
+// the directions of the end tangents (Schneider's method)
+fn fit_cubic(segments: &[Bezier3]) -> Bezier3 {
+    let samples_per_segment = 16;
+    let mut points: Vec<Vec2<f32>> = vec![segments[0].control_points[0]];
+    for segment in segments {
+        for i in 1..=samples_per_segment {
+            points.push(segment.get_point(i as f32 / samples_per_segment as f32));
+        }
+    }
+
+    // Start from a chord length parameterization
+    let mut u = vec![0.];
+    for w in points.windows(2) {
+        u.push(u.last().unwrap() + (w[1] - w[0]).mag());
+    }
+    let length = *u.last().unwrap();
+    if length > 0. {
+        u.iter_mut().for_each(|u_j| *u_j /= length);
+    }
+
+    let p_0 = points[0];
+    let p_3 = *points.last().unwrap();
+    let chord = p_3 - p_0;
+    let fallback = chord.try_normalize().unwrap_or(Vec2::new(1., 0.));
+    let t_1 = segments[0].tangent(0.).try_normalize().unwrap_or(fallback);
+    let t_2 = (-segments[segments.len() - 1].tangent(1.))
+        .try_normalize()
+        .unwrap_or(-fallback);
+
+    let mut fit = Bezier3::new(vec![
+        p_0,
+        p_0 + t_1 * chord.mag() / 3.,
+        p_3 + t_2 * chord.mag() / 3.,
+        p_3,
+    ]);
+    for _ in 0..8 {
+        // Normal equations for the handle lengths alpha_1, alpha_2 along t_1 and t_2
+        let (mut c_11, mut c_12, mut c_22, mut x_1, mut x_2) = (0., 0., 0., 0., 0.);
+        for (q, t) in points.iter().zip(u.iter()) {
+            let s = 1. - t;
+            let (b_0, b_1, b_2, b_3) = (s * s * s, 3. * s * s * t, 3. * s * t * t, t * t * t);
+            let a_1 = t_1 * b_1;
+            let a_2 = t_2 * b_2;
+            let r = *q - p_0 * (b_0 + b_1) - p_3 * (b_2 + b_3);
+            c_11 += a_1.dot(a_1);
+            c_12 += a_1.dot(a_2);
+            c_22 += a_2.dot(a_2);
+            x_1 += r.dot(a_1);
+            x_2 += r.dot(a_2);
+        }
+
+        let det = c_11 * c_22 - c_12 * c_12;
+        let alpha_1 = (x_1 * c_22 - x_2 * c_12) / det;
+        let alpha_2 = (c_11 * x_2 - c_12 * x_1) / det;
+        if !alpha_1.is_finite() || !alpha_2.is_finite() || alpha_1 <= 0. || alpha_2 <= 0. {
+            break;
+        }
+        fit = Bezier3::new(vec![p_0, p_0 + t_1 * alpha_1, p_3 + t_2 * alpha_2, p_3]);
+
+        // Move each parameter a Newton step towards the nearest point on the new fit
+        for (q, t) in points.iter().zip(u.iter_mut()) {
+            let d = fit.get_point(*t) - *q;
+            let d_1 = fit.first_derivative(*t);
+            let d_2 = fit.second_derivative(*t);
+            let denominator = d_1.dot(d_1) + d.dot(d_2);
+            if denominator != 0. {
+                *t = (*t - d.dot(d_1) / denominator).clamp(0., 1.);
+            }
+        }
+    }
+    fit
+}
+
+#[cfg(test)]
+mod path_tests {
+    use crate::math::basis::Basis;
+    use crate::math::bezier3::Bezier3;
+    use crate::math::fixtures::square;
+    use crate::math::matrix2x2::Matrix2x2;
+    use crate::math::path::{FillRule, Orientation, Path};
+    use crate::math::vec2::Vec2;
+
+    #[test]
+    fn winding() {
+        let path = square(0., 0., 1.);
+        assert!(path.is_connected(1e-6));
+        assert_eq!(path.orientation(), Orientation::Counterclockwise);
+        assert_eq!(path.winding_number(Vec2::new(0.5, 0.5)), 1);
+        assert_eq!(path.winding_number(Vec2::new(1.5, 0.5)), 0);
+
+        // The ray passes exactly through the corners
+        assert_eq!(path.winding_number(Vec2::new(-0.5, 0.)), 0);
+        assert_eq!(path.winding_number(Vec2::new(0.5, 1e-3)), 1);
+
+        let reversed = path.reversed();
+        assert_eq!(reversed.orientation(), Orientation::Clockwise);
+        assert_eq!(reversed.winding_number(Vec2::new(0.5, 0.5)), -1);
+    }
+
+    #[test]
+    fn fill_rules() {
+        // A pentagram winds twice around its centre and once around each point
+        let vertices: Vec<Vec2<f32>> = (0..5)
+            .map(|k| {
+                let theta = (90. + 144. * k as f32).to_radians();
+                Vec2::new(theta.cos(), theta.sin())
+            })
+            .collect();
+        let path = Path::new(
+            (0..5)
+                .map(|k| Bezier3::line(vertices[k], vertices[(k + 1) % 5]))
+                .collect(),
+            true,
+        );
+
+        let centre = Vec2::new(0., 0.);
+        assert_eq!(path.winding_number(centre), 2);
+        assert!(path.contains(centre, FillRule::NonZero));
+        assert!(!path.contains(centre, FillRule::EvenOdd));
+
+        let point = Vec2::new(0., 0.8);
+        assert_eq!(path.winding_number(point), 1);
+        assert!(path.contains(point, FillRule::EvenOdd));
+    }
+
+    #[test]
+    fn transforms() {
+        let path = square(0., 0., 1.);
+        let pivot = Vec2::new(0.5, 0.5);
+
+        // A quarter turn about the centre leaves the square in place
+        let rotation = Matrix2x2::identity().rotation(std::f32::consts::FRAC_PI_2);
+        let bounds = path
+            .transform_about(rotation, pivot, Vec2::new(0., 0.))
+            .bounds()
+            .unwrap();
+        for (b, expected) in bounds.iter().zip([0., 0., 1., 1.]) {
+            assert!((b - expected).abs() < 1e-5);
+        }
+
+        // Mirroring reverses the orientation
+        let mirror = Matrix2x2::identity().scale_xy(-1., 1.);
+        let mirrored = path.transform_about(mirror, pivot, Vec2::new(2., 0.));
+        assert_eq!(mirrored.orientation(), Orientation::Clockwise);
+        assert_eq!(mirrored.bounds().unwrap(), [2., 0., 3., 1.]);
+
+        // Bounds include the extrema of curved segments, not just their endpoints
+        let arch = Bezier3::new(vec![
+            Vec2::new(0., 0.),
+            Vec2::new(0., 1.),
+            Vec2::new(1., 1.),
+            Vec2::new(1., 0.),
+        ]);
+        assert!((arch.bounds()[3] - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn shared_endpoints_move_together() {
+        let mut path = square(0., 0., 1.);
+        path.move_handle(1, 3, Basis::Bezier, Vec2::new(2., 2.));
+        assert_eq!(path.segments[2].control_points[0], Vec2::new(2., 2.));
+        assert!(path.is_connected(0.));
+
+        // The first segment's start is joined to the last segment's end when closed
+        path.move_handle(0, 0, Basis::Bezier, Vec2::new(-1., 0.));
+        assert_eq!(path.end(), Some(Vec2::new(-1., 0.)));
+
+        // Interior handles leave the neighbours alone
+        let before = path.segments[1].clone();
+        path.move_handle(0, 1, Basis::Bezier, Vec2::new(0., -1.));
+        assert_eq!(path.segments[1], before);
+    }
+
+    #[test]
+    fn editing() {
+        let curve = Bezier3::new(vec![
+            Vec2::new(0., 0.),
+            Vec2::new(0., 1.),
+            Vec2::new(1., 1.),
+            Vec2::new(1., 0.),
+        ]);
+        let mut path = Path::new(vec![curve.clone()], false);
+
+        // Inserting a point and deleting it again recovers the curve
+        path.insert_point(0, 0.4);
+        assert_eq!(path.len(), 2);
+        assert!(path.is_connected(1e-6));
+        let (s, t, d) = path.nearest_point(Vec2::new(0.5, 0.75)).unwrap();
+        assert!(d < 1e-4 && (s, (t * 10.).round()) == (1, 2.));
+        assert!(path.delete_node(1));
+        for (p, q) in path.segments[0]
+            .control_points
+            .iter()
+            .zip(&curve.control_points)
+        {
+            assert!(p.approx_eq(*q, 1e-2));
+        }
+        assert!(!path.delete_node(0));
+
+        // Appending continues along the end tangent
+        path.append_segment(Vec2::new(1., -1.));
+        assert!(path.segments[1].tangent(0.).cross(curve.tangent(1.)).abs() < 1e-6);
+        assert!(path.delete_node(2));
+        assert_eq!(path.len(), 1);
+
+        // Appending at the end itself adds nothing
+        path.append_segment(path.end().unwrap());
+        assert_eq!(path.len(), 1);
+
+        // Closing bridges the gap with a straight segment
+        path.close();
+        assert_eq!(path.len(), 2);
+        assert_eq!(path.end(), path.start());
+        assert!(path.delete_node(0));
+        assert_eq!(path.len(), 1);
+    }
+
+    #[test]
+    fn open_paths_close_implicitly() {
+        let mut path = Path::new(Vec::new(), false);
+        path.push(Bezier3::line(Vec2::new(0., 0.), Vec2::new(1., 0.)));
+        path.push(Bezier3::line(Vec2::new(1.001, 0.), Vec2::new(0., 1.)));
+        assert!(path.is_connected(0.));
+        assert_eq!(path.segments[1].control_points[0], Vec2::new(1., 0.));
+        assert_eq!(path.closed_segments().len(), 3);
+        assert!((path.signed_area() - 0.5).abs() < 1e-3);
+        assert_eq!(path.winding_number(Vec2::new(0.25, 0.25)), 1);
+    }
+}
@@ -0,0 +1,295 @@
+use super::{
+    real::Real,
+    solvers::{solve_cubic, solve_quadratic},
+};
+use std::ops::{Add, Mul, Neg, Sub};
+
+// A polynomial in the power basis, c_0 + c_1*t + c_2*t^2 + ... Trailing zero coefficients
+// are dropped, so the zero polynomial has no coefficients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<T> {
+    pub coefficients: Vec<T>,
+}
+
+fn binomial(n: usize, k: usize) -> f32 {
+    (1..=k).fold(1., |c, i| c * (n + 1 - i) as f32 / i as f32)
+}
+
+impl<T: Real> Polynomial<T> {
+    pub fn new(mut coefficients: Vec<T>) -> Self {
+        while coefficients.last() == Some(&T::from(0.)) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(Vec::new())
+    }
+
+    pub fn constant(c: T) -> Self {
+        Self::new(vec![c])
+    }
+
+    // The polynomial t
+    pub fn identity() -> Self {
+        Self::new(vec![T::from(0.), T::from(1.)])
+    }
+
+    // Degree of the polynomial, or None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    // Evaluate with Horner's scheme
+    pub fn evaluate(&self, t: T) -> T {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(T::from(0.), |acc, &c| acc * t + c)
+    }
+
+    pub fn derivative(&self) -> Self {
+        Self::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, &c)| T::from(i as f32) * c)
+                .collect(),
+        )
+    }
+
+    // Antiderivative vanishing at t = 0
+    pub fn integral(&self) -> Self {
+        let mut coefficients = vec![T::from(0.)];
+        for (i, &c) in self.coefficients.iter().enumerate() {
+            coefficients.push(c / T::from((i + 1) as f32));
+        }
+        Self::new(coefficients)
+    }
+
+    // The polynomial p(q(t)), also by Horner's scheme
+    pub fn compose(&self, q: &Polynomial<T>) -> Self {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Self::zero(), |acc, &c| acc * q.clone() + Self::constant(c))
+    }
+
+    // Convert from Bernstein coefficients b_0..b_n of degree n = b.len() - 1
+    pub fn from_bernstein(b: &[T]) -> Self {
+        let n = match b.len().checked_sub(1) {
+            Some(n) => n,
+            None => return Self::zero(),
+        };
+        let coefficients = (0..=n)
+            .map(|k| {
+                let mut c = T::from(0.);
+                for (i, &b_i) in b.iter().enumerate().take(k + 1) {
+                    let sign = if (k - i) % 2 == 0 { 1. } else { -1. };
+                    c += T::from(sign * binomial(k, i)) * b_i;
+                }
+                T::from(binomial(n, k)) * c
+            })
+            .collect();
+        Self::new(coefficients)
+    }
+
+    // Bernstein coefficients of degree n, which must be at least the degree of the polynomial
+    pub fn to_bernstein(&self, n: usize) -> Vec<T> {
+        assert!(self.coefficients.len() <= n + 1);
+        (0..=n)
+            .map(|i| {
+                let mut b = T::from(0.);
+                for (k, &a_k) in self.coefficients.iter().enumerate().take(i + 1) {
+                    b += T::from(binomial(i, k) / binomial(n, k)) * a_k;
+                }
+                b
+            })
+            .collect()
+    }
+}
+
+impl Polynomial<f32> {
+    // Real roots in ascending order. Above degree three the roots are bracketed between the
+    // extrema, which are the roots of the derivative, and refined by bisection.
+    pub fn roots(&self) -> Vec<f32> {
+        let c = &self.coefficients;
+        match c.len() {
+            0 | 1 => Vec::new(),
+            2 => vec![-c[0] / c[1]],
+            3 => solve_quadratic(c[2], c[1], c[0]),
+            4 => solve_cubic(c[3], c[2], c[1], c[0]),
+            n => {
+                // Cauchy's bound on the magnitude of the roots
+                let lead = c[n - 1];
+                let bound = 1.
+                    + c[..n - 1]
+                        .iter()
+                        .map(|c_i| (c_i / lead).abs())
+                        .fold(0., f32::max);
+
+                let mut knots = vec![-bound];
+                knots.extend(self.derivative().roots());
+                knots.push(bound);
+
+                let mut roots: Vec<f32> = Vec::new();
+                for w in knots.windows(2) {
+                    if let Some(r) = self.bisect(w[0], w[1]) {
+                        if !roots.last().is_some_and(|&last| r - last <= 1e-6) {
+                            roots.push(r);
+                        }
+                    }
+                }
+                roots
+            }
+        }
+    }
+
+    // Root of a polynomial that is monotone on [lo, hi], if it changes sign there. Roots
+    // touching zero at an endpoint (double roots at extrema) are accepted too.
+    fn bisect(&self, mut lo: f32, mut hi: f32) -> Option<f32> {
+        let scale = self.coefficients.iter().map(|c| c.abs()).fold(0., f32::max);
+        let (f_lo, f_hi) = (self.evaluate(lo), self.evaluate(hi));
+        if f_lo.abs() <= 1e-6 * scale {
+            return Some(lo);
+        }
+        if f_hi.abs() <= 1e-6 * scale {
+            return Some(hi);
+        }
+        if f_lo * f_hi > 0. {
+            return None;
+        }
+
+        for _ in 0..64 {
+            let mid = 0.5 * (lo + hi);
+            if mid <= lo || mid >= hi {
+                break;
+            }
+            if self.evaluate(mid) * f_lo > 0. {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        Some(0.5 * (lo + hi))
+    }
+}
+
+impl<T: Real> Add for Polynomial<T> {
+    type Output = Polynomial<T>;
+    fn add(self, rhs: Polynomial<T>) -> Polynomial<T> {
+        let n = self.coefficients.len().max(rhs.coefficients.len());
+        let zero = T::from(0.);
+        Polynomial::new(
+            (0..n)
+                .map(|i| {
+                    *self.coefficients.get(i).unwrap_or(&zero)
+                        + *rhs.coefficients.get(i).unwrap_or(&zero)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<T: Real> Neg for Polynomial<T> {
+    type Output = Polynomial<T>;
+    fn neg(self) -> Polynomial<T> {
+        Polynomial::new(self.coefficients.iter().map(|&c| -c).collect())
+    }
+}
+
+impl<T: Real> Sub for Polynomial<T> {
+    type Output = Polynomial<T>;
+    fn sub(self, rhs: Polynomial<T>) -> Polynomial<T> {
+        self + -rhs
+    }
+}
+
+impl<T: Real> Mul for Polynomial<T> {
+    type Output = Polynomial<T>;
+    fn mul(self, rhs: Polynomial<T>) -> Polynomial<T> {
+        if self.coefficients.is_empty() || rhs.coefficients.is_empty() {
+            return Polynomial::zero();
+        }
+        let mut coefficients =
+            vec![T::from(0.); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            for (j, &b) in rhs.coefficients.iter().enumerate() {
+                coefficients[i + j] += a * b;
+            }
+        }
+        Polynomial::new(coefficients)
+    }
+}
+
+// Scalar multiplication: Polynomial<T> * T -> Polynomial<T>
+impl<T: Real> Mul<T> for Polynomial<T> {
+    type Output = Polynomial<T>;
+    fn mul(self, rhs: T) -> Polynomial<T> {
+        Polynomial::new(self.coefficients.iter().map(|&c| c * rhs).collect())
+    }
+}
+
+#[cfg(test)]
+mod polynomial_tests {
+    use crate::math::polynomial::Polynomial;
+
+    #[test]
+    fn arithmetic() {
+        // (1 + t) * (1 - t) = 1 - t^2
+        let p = Polynomial::new(vec![1., 1.]);
+        let q = Polynomial::new(vec![1., -1.]);
+        let product = p.clone() * q.clone();
+        assert_eq!(product, Polynomial::new(vec![1., 0., -1.]));
+        assert_eq!(product.evaluate(3.), -8.);
+        assert_eq!((p.clone() + q.clone()), Polynomial::constant(2.));
+        assert_eq!((p.clone() - p.clone()).degree(), None);
+
+        // (1 - t^2) o (1 + t) = -2t - t^2
+        assert_eq!(product.compose(&p), Polynomial::new(vec![0., -2., -1.]));
+
+        let cubic = Polynomial::new(vec![1., 2., 3., 4.]);
+        assert_eq!(cubic.derivative(), Polynomial::new(vec![2., 6., 12.]));
+        assert_eq!(
+            cubic.derivative().integral(),
+            cubic - Polynomial::constant(1.)
+        );
+    }
+
+    #[test]
+    fn roots() {
+        let linear = |r: f32| Polynomial::new(vec![-r, 1.]);
+        let quintic = linear(-0.5) * linear(1.) * linear(2.) * linear(3.) * linear(4.);
+        let roots = quintic.roots();
+        assert_eq!(roots.len(), 5);
+        for (r, expected) in roots.iter().zip([-0.5, 1., 2., 3., 4.]) {
+            assert!((r - expected).abs() < 1e-3);
+        }
+
+        // t^4 + 1 has no real roots, (t^2 - 1)^2 has two double roots
+        assert!(Polynomial::new(vec![1., 0., 0., 0., 1.]).roots().is_empty());
+        let squared = Polynomial::new(vec![-1., 0., 1.]) * Polynomial::new(vec![-1., 0., 1.]);
+        let roots = squared.roots();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] + 1.).abs() < 1e-3 && (roots[1] - 1.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn bernstein_round_trip() {
+        let b = [0.2, 0.1, 0.4, 0.9];
+        let p = Polynomial::from_bernstein(&b);
+        // B(0.5) = (b_0 + 3 b_1 + 3 b_2 + b_3) / 8
+        assert!((p.evaluate(0.5) - (0.2 + 0.3 + 1.2 + 0.9) / 8.).abs() < 1e-6);
+        for (x, y) in p.to_bernstein(3).iter().zip(b) {
+            assert!((x - y).abs() < 1e-6);
+        }
+
+        // Degree elevation of a line keeps the control points evenly spaced
+        let elevated = Polynomial::new(vec![1., 3.]).to_bernstein(3);
+        for (x, y) in elevated.iter().zip([1., 2., 3., 4.]) {
+            assert!((x - y).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use super::{path::Orientation, rational::Rational, vec2::Vec2};
+use std::cmp::Ordering;
+
+// Relative error bound on the floating point orientation determinant (Shewchuk's
+// ccwerrboundA for f64)
+const ORIENTATION_BOUND: f64 = (3. + 16. * f64::EPSILON) * f64::EPSILON;
+
+fn from_ordering(sign: Ordering) -> Option<Orientation> {
+    match sign {
+        Ordering::Greater => Some(Orientation::Counterclockwise),
+        Ordering::Less => Some(Orientation::Clockwise),
+        Ordering::Equal => None,
+    }
+}
+
+// Which way a -> b -> c turns, or None if the points are collinear. The determinant is
+// first evaluated in f64; only when it is too small to trust is it recomputed exactly.
+pub fn orientation(a: Vec2<f32>, b: Vec2<f32>, c: Vec2<f32>) -> Option<Orientation> {
+    let left = (a.x as f64 - c.x as f64) * (b.y as f64 - c.y as f64);
+    let right = (a.y as f64 - c.y as f64) * (b.x as f64 - c.x as f64);
+    let det = left - right;
+    if det.abs() > ORIENTATION_BOUND * (left.abs() + right.abs()) {
+        return from_ordering(det.partial_cmp(&0.).unwrap());
+    }
+
+    let (a, b, c) = (Vec2::exact(a), Vec2::exact(b), Vec2::exact(c));
+    from_ordering((&b - &a).cross(&(&c - &a)).signum())
+}
+
+// Check whether the closed segments p_0 p_1 and q_0 q_1 share a point, exactly
+pub fn segments_intersect(p_0: Vec2<f32>, p_1: Vec2<f32>, q_0: Vec2<f32>, q_1: Vec2<f32>) -> bool {
+    let d_1 = orientation(q_0, q_1, p_0);
+    let d_2 = orientation(q_0, q_1, p_1);
+    let d_3 = orientation(p_0, p_1, q_0);
+    let d_4 = orientation(p_0, p_1, q_1);
+
+    // Points strictly on opposite sides of each other's lines
+    if d_1.is_some() && d_2.is_some() && d_1 != d_2 && d_3.is_some() && d_4.is_some() && d_3 != d_4
+    {
+        return true;
+    }
+
+    // Otherwise they can only meet where an endpoint lies on the other segment
+    let within = |a: Vec2<f32>, b: Vec2<f32>, p: Vec2<f32>| {
+        a.x.min(b.x) <= p.x && p.x <= a.x.max(b.x) && a.y.min(b.y) <= p.y && p.y <= a.y.max(b.y)
+    };
+    (d_1.is_none() && within(q_0, q_1, p_0))
+        || (d_2.is_none() && within(q_0, q_1, p_1))
+        || (d_3.is_none() && within(p_0, p_1, q_0))
+        || (d_4.is_none() && within(p_0, p_1, q_1))
+}
+
+// Exact intersection of the lines through p_0 p_1 and q_0 q_1, or None if they are parallel
+pub fn line_intersection(
+    p_0: Vec2<f32>,
+    p_1: Vec2<f32>,
+    q_0: Vec2<f32>,
+    q_1: Vec2<f32>,
+) -> Option<Vec2<Rational>> {
+    let (p_0, p_1) = (Vec2::exact(p_0), Vec2::exact(p_1));
+    let (q_0, q_1) = (Vec2::exact(q_0), Vec2::exact(q_1));
+    let r = &p_1 - &p_0;
+    let s = &q_1 - &q_0;
+
+    let denominator = r.cross(&s);
+    if denominator.is_zero() {
+        return None;
+    }
+    let t = (&q_0 - &p_0).cross(&s) / denominator;
+    Some(&p_0 + &r.scale(&t))
+}
+
+#[cfg(test)]
+mod predicates_tests {
+    use crate::math::path::Orientation;
+    use crate::math::predicates::{line_intersection, orientation, segments_intersect};
+    use crate::math::rational::Rational;
+    use crate::math::vec2::Vec2;
+
+    #[test]
+    fn orientation_near_degenerate() {
+        let a = Vec2::new(0., 0.);
+        let b = Vec2::new(1., 1.);
+        assert_eq!(
+            orientation(a, b, Vec2::new(0., 1.)),
+            Some(Orientation::Counterclockwise)
+        );
+        assert_eq!(orientation(a, b, Vec2::new(3., 3.)), None);
+
+        // Points a single ulp off the diagonal are still classified correctly
+        let c = Vec2::new(0.75, 0.75_f32.next_up());
+        assert_eq!(orientation(a, b, c), Some(Orientation::Counterclockwise));
+        let c = Vec2::new(0.75, 0.75_f32.next_down());
+        assert_eq!(orientation(a, b, c), Some(Orientation::Clockwise));
+    }
+
+    #[test]
+    fn segments() {
+        let o = Vec2::new(0., 0.);
+        assert!(segments_intersect(
+            o,
+            Vec2::new(2., 2.),
+            Vec2::new(0., 2.),
+            Vec2::new(2., 0.)
+        ));
+        // Touching at an endpoint counts, collinear but apart does not
+        assert!(segments_intersect(
+            o,
+            Vec2::new(1., 1.),
+            Vec2::new(1., 1.),
+            Vec2::new(2., 0.)
+        ));
+        assert!(!segments_intersect(
+            o,
+            Vec2::new(1., 0.),
+            Vec2::new(2., 0.),
+            Vec2::new(3., 0.)
+        ));
+
+        let p =
+            line_intersection(o, Vec2::new(3., 1.), Vec2::new(0., 1.), Vec2::new(1., 0.)).unwrap();
+        assert_eq!(p.x.to_string(), "3/4");
+        assert_eq!(p.y.to_string(), "1/4");
+        assert_eq!(p.y, Rational::from_f32(0.25).unwrap());
+        assert!(
+            line_intersection(o, Vec2::new(1., 0.), Vec2::new(0., 1.), Vec2::new(1., 1.)).is_none()
+        );
+    }
+}
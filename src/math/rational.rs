@@ -0,0 +1,259 @@
+use super::{
+    integer::{forward_owned, BigInt},
+    vec2::Vec2,
+};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::*;
+
+// Exact rational number num/den in lowest terms with den > 0. Every finite f32 converts
+// exactly, so predicates evaluated over rationals have no rounding error at all. It is not
+// Copy, so it cannot implement Real; curves are evaluated over it by Bezier3::evaluate_exact.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: BigInt,
+    den: BigInt,
+}
+
+impl Rational {
+    pub fn new(num: BigInt, den: BigInt) -> Self {
+        assert!(!den.is_zero(), "zero denominator");
+        let g = num.gcd(&den);
+        let (num, den) = if g.is_zero() {
+            (num, den)
+        } else {
+            (&num / &g, &den / &g)
+        };
+        if den.is_negative() {
+            Self {
+                num: -num,
+                den: -den,
+            }
+        } else {
+            Self { num, den }
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::from(0)
+    }
+
+    pub fn one() -> Self {
+        Self::from(1)
+    }
+
+    // Exact value of a finite float, or None for infinities and NaN
+    pub fn from_f32(x: f32) -> Option<Self> {
+        if !x.is_finite() {
+            return None;
+        }
+
+        // x = (-1)^sign * mantissa * 2^exponent
+        let bits = x.to_bits();
+        let biased = ((bits >> 23) & 0xff) as i32;
+        let fraction = (bits & 0x7fffff) as i64;
+        let (mantissa, exponent) = if biased == 0 {
+            (fraction, -149)
+        } else {
+            (fraction | 1 << 23, biased - 150)
+        };
+        let mantissa = if bits >> 31 == 1 { -mantissa } else { mantissa };
+
+        let mantissa = BigInt::from(mantissa);
+        Some(if exponent >= 0 {
+            Self::new(&mantissa * &BigInt::pow2(exponent as usize), BigInt::one())
+        } else {
+            Self::new(mantissa, BigInt::pow2((-exponent) as usize))
+        })
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        &self.num
+    }
+
+    pub fn denominator(&self) -> &BigInt {
+        &self.den
+    }
+
+    pub fn signum(&self) -> Ordering {
+        self.num.cmp(&BigInt::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let (n, n_exponent) = self.num.to_f64_parts();
+        let (d, d_exponent) = self.den.to_f64_parts();
+        n / d * 2_f64.powi(n_exponent - d_exponent)
+    }
+
+    pub fn to_f32(&self) -> f32 {
+        self.to_f64() as f32
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Rational {
+            num: BigInt::from(n),
+            den: BigInt::one(),
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        (&self.num * &other.den).cmp(&(&other.num * &self.den))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == BigInt::one() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+// Negation: -&Rational -> Rational
+impl Neg for &Rational {
+    type Output = Rational;
+    fn neg(self) -> Rational {
+        Rational {
+            num: -&self.num,
+            den: self.den.clone(),
+        }
+    }
+}
+
+// Addition: &Rational + &Rational -> Rational
+impl Add for &Rational {
+    type Output = Rational;
+    fn add(self, rhs: &Rational) -> Rational {
+        Rational::new(
+            &(&self.num * &rhs.den) + &(&rhs.num * &self.den),
+            &self.den * &rhs.den,
+        )
+    }
+}
+
+// Subtraction: &Rational - &Rational -> Rational
+impl Sub for &Rational {
+    type Output = Rational;
+    fn sub(self, rhs: &Rational) -> Rational {
+        self + &(-rhs)
+    }
+}
+
+// Multiplication: &Rational * &Rational -> Rational
+impl Mul for &Rational {
+    type Output = Rational;
+    fn mul(self, rhs: &Rational) -> Rational {
+        Rational::new(&self.num * &rhs.num, &self.den * &rhs.den)
+    }
+}
+
+// Division: &Rational / &Rational -> Rational
+impl Div for &Rational {
+    type Output = Rational;
+    fn div(self, rhs: &Rational) -> Rational {
+        Rational::new(&self.num * &rhs.den, &self.den * &rhs.num)
+    }
+}
+
+forward_owned!(Rational, Add add, Sub sub, Mul mul, Div div);
+
+// Rational isn't Copy, so it can't be a Real; vectors of rationals get their own operations
+impl Vec2<Rational> {
+    // Exact copy of a float vector, which must be finite
+    pub fn exact(v: Vec2<f32>) -> Self {
+        Vec2 {
+            x: Rational::from_f32(v.x).unwrap(),
+            y: Rational::from_f32(v.y).unwrap(),
+        }
+    }
+
+    pub fn to_f32(&self) -> Vec2<f32> {
+        Vec2::new(self.x.to_f32(), self.y.to_f32())
+    }
+
+    pub fn dot(&self, rhs: &Vec2<Rational>) -> Rational {
+        &self.x * &rhs.x + &self.y * &rhs.y
+    }
+
+    pub fn cross(&self, rhs: &Vec2<Rational>) -> Rational {
+        &self.x * &rhs.y - &self.y * &rhs.x
+    }
+
+    pub fn scale(&self, s: &Rational) -> Self {
+        Vec2 {
+            x: &self.x * s,
+            y: &self.y * s,
+        }
+    }
+}
+
+// Addition: &Vec2<Rational> + &Vec2<Rational> -> Vec2<Rational>
+impl Add for &Vec2<Rational> {
+    type Output = Vec2<Rational>;
+    fn add(self, rhs: &Vec2<Rational>) -> Vec2<Rational> {
+        Vec2 {
+            x: &self.x + &rhs.x,
+            y: &self.y + &rhs.y,
+        }
+    }
+}
+
+// Subtraction: &Vec2<Rational> - &Vec2<Rational> -> Vec2<Rational>
+impl Sub for &Vec2<Rational> {
+    type Output = Vec2<Rational>;
+    fn sub(self, rhs: &Vec2<Rational>) -> Vec2<Rational> {
+        Vec2 {
+            x: &self.x - &rhs.x,
+            y: &self.y - &rhs.y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod rational_tests {
+    use crate::math::integer::BigInt;
+    use crate::math::rational::Rational;
+
+    #[test]
+    fn arithmetic() {
+        let third = Rational::new(BigInt::from(1), BigInt::from(3));
+        let sixth = Rational::new(BigInt::from(-2), BigInt::from(-12));
+        assert_eq!(sixth.to_string(), "1/6");
+        assert_eq!((&third + &sixth).to_string(), "1/2");
+        assert_eq!((&sixth - &third).to_string(), "-1/6");
+        assert_eq!((&third * &sixth).to_string(), "1/18");
+        assert_eq!((&third / &sixth).to_string(), "2");
+        assert!(sixth < third);
+        assert!((third.to_f64() - 1. / 3.).abs() < 1e-15);
+    }
+
+    #[test]
+    fn floats_convert_exactly() {
+        for x in [0.1_f32, -3.75, 1e-40, 3e38, 0.] {
+            let r = Rational::from_f32(x).unwrap();
+            assert_eq!(r.to_f32(), x);
+        }
+        assert_eq!(Rational::from_f32(0.375).unwrap().to_string(), "3/8");
+        assert_eq!(Rational::from_f32(f32::NAN), None);
+
+        // 0.1 + 0.2 is exactly the sum of the two floats, which is not the float 0.3
+        let sum = Rational::from_f32(0.1).unwrap() + Rational::from_f32(0.2).unwrap();
+        assert_ne!(sum, Rational::from_f32(0.3).unwrap());
+    }
+}
@@ -1,4 +1,6 @@
-pub fn _newton(mut x: f32, f: impl Fn(f32) -> f32, df: impl Fn(f32) -> f32, tol: f32) -> f32 {
+use super::{dual::Dual, interval::Interval};
+
+pub fn newton(mut x: f32, f: impl Fn(f32) -> f32, df: impl Fn(f32) -> f32, tol: f32) -> f32 {
     for _ in 0..10 {
         let x_n = x - f(x) / df(x);
         if (x - x_n).abs() <= tol {
@@ -35,3 +37,137 @@ pub fn newton2(
     }
     (x, y)
 }
+
+// Two-dimensional Newton's method taking only the residuals f and g. Each column of the
+// Jacobian comes from seeding one variable as the dual direction.
+pub fn newton2_ad(
+    x: f32,
+    y: f32,
+    f: impl Fn(Dual, Dual) -> Dual,
+    g: impl Fn(Dual, Dual) -> Dual,
+    tol: f32,
+) -> (f32, f32) {
+    let (c, v) = (Dual::constant, Dual::variable);
+    newton2(
+        x,
+        y,
+        |x, y| f(c(x), c(y)).re,
+        |x, y| f(v(x), c(y)).eps,
+        |x, y| f(c(x), v(y)).eps,
+        |x, y| g(c(x), c(y)).re,
+        |x, y| g(v(x), c(y)).eps,
+        |x, y| g(c(x), v(y)).eps,
+        tol,
+    )
+}
+
+// Enclose every root of f within domain in intervals no wider than tol, in ascending
+// order. f must return an interval containing every value the function takes over its
+// argument. Boxes whose image excludes zero are discarded, so no root is ever missed,
+// although a returned interval may turn out to hold none.
+pub fn isolate_roots(
+    f: impl Fn(Interval<f32>) -> Interval<f32>,
+    domain: Interval<f32>,
+    tol: f32,
+) -> Vec<Interval<f32>> {
+    let mut roots: Vec<Interval<f32>> = Vec::new();
+    let mut stack = vec![domain];
+    while let Some(x) = stack.pop() {
+        if !f(x).contains(0.) {
+            continue;
+        }
+
+        let (left, right) = x.split();
+        if x.width() > tol && left.width() < x.width() && right.width() < x.width() {
+            // Search the left half first so roots come out in order
+            stack.push(right);
+            stack.push(left);
+        } else if let Some(last) = roots.last_mut().filter(|r| r.intersects(&x)) {
+            *last = last.hull(&x);
+        } else {
+            roots.push(x);
+        }
+    }
+    roots
+}
+
+// Real roots of a*x^2 + b*x + c = 0, in ascending order
+pub fn solve_quadratic(a: f32, b: f32, c: f32) -> Vec<f32> {
+    let (a, b, c) = (a as f64, b as f64, c as f64);
+    let mut roots = Vec::new();
+
+    if a.abs() <= 1e-9 * (b.abs() + c.abs()) || a == 0. {
+        // Degenerate to a linear equation
+        if b != 0. {
+            roots.push((-c / b) as f32);
+        }
+        return roots;
+    }
+
+    let disc = b * b - 4. * a * c;
+    if disc < 0. {
+        return roots;
+    } else if disc == 0. {
+        roots.push((-b / (2. * a)) as f32);
+    } else {
+        // Avoid cancellation by computing the larger magnitude root first
+        let q = -0.5 * (b + b.signum() * disc.sqrt());
+        let (x_0, x_1) = (q / a, c / q);
+        roots.push(x_0.min(x_1) as f32);
+        roots.push(x_0.max(x_1) as f32);
+    }
+    roots
+}
+
+// Real roots of a*x^3 + b*x^2 + c*x + d = 0, in ascending order
+pub fn solve_cubic(a: f32, b: f32, c: f32, d: f32) -> Vec<f32> {
+    let scale = (b.abs() + c.abs() + d.abs()) as f64;
+    if (a as f64).abs() <= 1e-7 * scale || a == 0. {
+        return solve_quadratic(b, c, d);
+    }
+
+    // Reduce to the depressed cubic x = y - b/3a, y^3 + p*y + q = 0
+    let (a, b, c, d) = (a as f64, b as f64, c as f64, d as f64);
+    let (b, c, d) = (b / a, c / a, d / a);
+    let p = c - b * b / 3.;
+    let q = 2. * b.powi(3) / 27. - b * c / 3. + d;
+    let shift = -b / 3.;
+
+    let disc = q * q / 4. + p.powi(3) / 27.;
+    let mut roots: Vec<f64> = Vec::new();
+    if disc.abs() <= 1e-14 * (q * q / 4. + p.powi(3).abs() / 27.).max(1e-30) {
+        // Repeated roots
+        if p == 0. {
+            roots.push(shift);
+        } else {
+            roots.push(3. * q / p + shift);
+            roots.push(-3. * q / (2. * p) + shift);
+        }
+    } else if disc > 0. {
+        // One real root (Cardano)
+        let sqrt_disc = disc.sqrt();
+        roots.push((-q / 2. + sqrt_disc).cbrt() + (-q / 2. - sqrt_disc).cbrt() + shift);
+    } else {
+        // Three real roots (trigonometric method)
+        let r = 2. * (-p / 3.).sqrt();
+        let phi = (3. * q / (p * r)).clamp(-1., 1.).acos() / 3.;
+        for k in 0..3 {
+            roots.push(r * (phi - 2. * std::f64::consts::PI * k as f64 / 3.).cos() + shift);
+        }
+    }
+
+    // Polish each root with a few Newton iterations on the monic polynomial
+    for x in roots.iter_mut() {
+        for _ in 0..4 {
+            let f = ((*x + b) * *x + c) * *x + d;
+            let df = (3. * *x + 2. * b) * *x + c;
+            if df == 0. {
+                break;
+            }
+            *x -= f / df;
+        }
+    }
+
+    roots.sort_by(|x, y| x.total_cmp(y));
+    roots.into_iter().map(|x| x as f32).collect()
+}
@@ -0,0 +1,67 @@
+// Axis tick placement. Steps are "nice" numbers, 1, 2 or 5 times a power of ten, so tick
+// labels stay short at any zoom level.
+
+// The nice step dividing span into roughly target intervals
+pub fn nice_step(span: f64, target: usize) -> f64 {
+    let raw = span.abs() / target.max(1) as f64;
+    if !raw.is_finite() || raw <= 0. {
+        return 1.;
+    }
+    let magnitude = 10_f64.powf(raw.log10().floor());
+    let mantissa = raw / magnitude;
+    let nice = if mantissa < 1.5 {
+        1.
+    } else if mantissa < 3.5 {
+        2.
+    } else if mantissa < 7.5 {
+        5.
+    } else {
+        10.
+    };
+    nice * magnitude
+}
+
+// Minor step subdividing a nice major step: fifths of 1s and 5s, quarters of 2s
+pub fn minor_step(major: f64) -> f64 {
+    let mantissa = major / 10_f64.powf(major.log10().floor());
+    if (mantissa - 2.).abs() < 0.1 {
+        major / 4.
+    } else {
+        major / 5.
+    }
+}
+
+// Multiples of step within [min, max], in increasing order
+pub fn ticks(min: f64, max: f64, step: f64) -> Vec<f64> {
+    let (min, max) = (min.min(max), min.max(max));
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+// Decimal places needed to label multiples of step exactly
+pub fn decimals(step: f64) -> usize {
+    (-step.log10().floor()).max(0.) as usize
+}
+
+#[cfg(test)]
+mod ticks_tests {
+    use crate::math::ticks::{decimals, minor_step, nice_step, ticks};
+
+    #[test]
+    fn nice_numbers() {
+        assert_eq!(nice_step(1., 10), 0.1);
+        assert_eq!(nice_step(1., 4), 0.2);
+        assert_eq!(nice_step(37., 8), 5.);
+        assert_eq!(nice_step(-0.003, 5), 0.0005);
+        assert!((minor_step(0.2) - 0.05).abs() < 1e-12);
+        assert!((minor_step(5.) - 1.).abs() < 1e-12);
+
+        let t = ticks(-0.25, 0.5, 0.2);
+        assert_eq!(t.len(), 4);
+        assert!((t[0] + 0.2).abs() < 1e-12 && (t[3] - 0.4).abs() < 1e-12);
+        assert_eq!(decimals(0.05), 2);
+        assert_eq!(decimals(5.), 0);
+        assert_eq!(format!("{:.*}", decimals(0.2), t[1]), "0.0");
+    }
+}
@@ -26,9 +26,72 @@ impl<T: Real> Vec2<T> {
     }
 
     pub fn normal(&self) -> Self {
+        self.perp()
+    }
+
+    // Normalize, or return None for a zero (or non-finite) vector instead of NaN
+    pub fn try_normalize(&self) -> Option<Self> {
+        let magnitude: f32 = self.mag().into();
+        if magnitude > 0. && magnitude.is_finite() {
+            Some(*self / T::from(magnitude))
+        } else {
+            None
+        }
+    }
+
+    pub fn dot(&self, rhs: Vec2<T>) -> T {
+        self.x * rhs.x + self.y * rhs.y
+    }
+
+    // z-component of the 3D cross product, positive when rhs is counterclockwise of self
+    pub fn cross(&self, rhs: Vec2<T>) -> T {
+        self.x * rhs.y - self.y * rhs.x
+    }
+
+    // The vector rotated a quarter turn counterclockwise
+    pub fn perp(&self) -> Self {
         Vec2::new(-self.y, self.x)
     }
 
+    pub fn distance(&self, rhs: Vec2<T>) -> T {
+        (rhs - *self).mag()
+    }
+
+    // Linear interpolation, returning self at t = 0 and rhs at t = 1
+    pub fn lerp(&self, rhs: Vec2<T>, t: T) -> Self {
+        *self + (rhs - *self) * t
+    }
+
+    // Rotate counterclockwise by theta radians
+    pub fn rotate(&self, theta: f32) -> Self {
+        let (sin, cos) = (T::from(theta.sin()), T::from(theta.cos()));
+        Vec2::new(cos * self.x - sin * self.y, sin * self.x + cos * self.y)
+    }
+
+    // Signed angle in radians from self to rhs, in (-pi, pi]
+    pub fn angle_between(&self, rhs: Vec2<T>) -> f32 {
+        let cross: f32 = self.cross(rhs).into();
+        let dot: f32 = self.dot(rhs).into();
+        cross.atan2(dot)
+    }
+
+    // Component of self along rhs. Projecting onto a zero vector gives zero.
+    pub fn project_onto(&self, rhs: Vec2<T>) -> Self {
+        let zero = T::from(0.);
+        let length_squared = rhs.dot(rhs);
+        if length_squared == zero {
+            Vec2::new(zero, zero)
+        } else {
+            rhs * (self.dot(rhs) / length_squared)
+        }
+    }
+
+    // Check that each component is within eps of rhs
+    pub fn approx_eq(&self, rhs: Vec2<T>, eps: T) -> bool {
+        let d = *self - rhs;
+        d.x <= eps && -d.x <= eps && d.y <= eps && -d.y <= eps
+    }
+
     // Return the Euclidean norm of the vector
     // TODO: Runtime type checking?
     pub fn mag(&self) -> T {
@@ -144,14 +207,17 @@ where
     }
 }
 
-// ~~Multiplication~~ inner product: Vec2<T> * Vec2<T> -> T
+// Elementwise multiplication: Vec2<T> * Vec2<T> -> Vec2<T>
 impl<T> Mul<Vec2<T>> for Vec2<T>
 where
     T: Real,
 {
-    type Output = T;
-    fn mul(self, rhs: Vec2<T>) -> T {
-        self.x * rhs.x + self.y * rhs.y
+    type Output = Vec2<T>;
+    fn mul(self, rhs: Vec2<T>) -> Vec2<T> {
+        Vec2 {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+        }
     }
 }
 
@@ -210,14 +276,17 @@ where
     }
 }
 
-// ~~Division~~ cross product in R2: Vec2<T> / Vec2<T> -> T
+// Elementwise division: Vec2<T> / Vec2<T> -> Vec2<T>
 impl<T> Div<Vec2<T>> for Vec2<T>
 where
     T: Real,
 {
-    type Output = T;
-    fn div(self, rhs: Vec2<T>) -> T {
-        self.x * rhs.y - self.y * rhs.x
+    type Output = Vec2<T>;
+    fn div(self, rhs: Vec2<T>) -> Vec2<T> {
+        Vec2 {
+            x: self.x / rhs.x,
+            y: self.y / rhs.y,
+        }
     }
 }
 
@@ -269,5 +338,36 @@ mod vec2_tests {
         let a_minus_b = Vec2::new(2., -4.);
         assert_eq!(a - ZERO, a);
         assert_eq!(a - b, a_minus_b);
+
+        // Check elementwise multiplication and division
+        assert_eq!(a * b, Vec2::new(35., 221.));
+        assert_eq!(a_plus_b / Vec2::new(4., 5.), Vec2::new(3., 6.));
+    }
+
+    #[test]
+    fn products_and_angles() {
+        let a = Vec2::new(3., 4.);
+        let b = Vec2::new(-2., 1.);
+        assert_eq!(a.dot(b), -2.);
+        assert_eq!(a.cross(b), 11.);
+        assert_eq!(a.perp(), Vec2::new(-4., 3.));
+        assert_eq!(a.distance(Vec2::new(0., 0.)), 5.);
+        assert_eq!(a.lerp(b, 0.5), Vec2::new(0.5, 2.5));
+
+        let x = Vec2::new(1., 0.);
+        let y = Vec2::new(0., 1.);
+        assert!(x.rotate(std::f32::consts::FRAC_PI_2).approx_eq(y, 1e-6));
+        assert!((x.angle_between(y) - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert!((y.angle_between(x) + std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert_eq!(a.project_onto(x), Vec2::new(3., 0.));
+        assert_eq!(a.project_onto(Vec2::new(0., 0.)), Vec2::new(0., 0.));
+        assert!(!a.approx_eq(b, 1.));
+    }
+
+    #[test]
+    fn normalization() {
+        let v: Vec2<f32> = Vec2::new(0., 0.);
+        assert_eq!(v.try_normalize(), None);
+        assert_eq!(Vec2::new(0., 2.).try_normalize(), Some(Vec2::new(0., 1.)));
     }
 }
@@ -0,0 +1,40 @@
+use druid::menu::{Menu, MenuEventCtx, MenuItem};
+use druid::{Env, LocalizedString, SysMods, WindowId};
+
+use crate::app_data::AppData;
+
+// The Edit menu, naming the command each entry would undo or redo
+fn edit_menu() -> Menu<AppData> {
+    let undo = MenuItem::new(
+        |data: &AppData, _env: &Env| match data.history.undo_name() {
+            Some(name) => format!("Undo {}", name),
+            None => "Undo".to_string(),
+        },
+    )
+    .on_activate(|_ctx: &mut MenuEventCtx, data: &mut AppData, _env: &Env| data.undo())
+    .hotkey(SysMods::Cmd, "z")
+    .enabled_if(|data: &AppData, _env: &Env| data.history.undo_name().is_some());
+
+    let redo = MenuItem::new(
+        |data: &AppData, _env: &Env| match data.history.redo_name() {
+            Some(name) => format!("Redo {}", name),
+            None => "Redo".to_string(),
+        },
+    )
+    .on_activate(|_ctx: &mut MenuEventCtx, data: &mut AppData, _env: &Env| data.redo())
+    .hotkey(SysMods::CmdShift, "Z")
+    .enabled_if(|data: &AppData, _env: &Env| data.history.redo_name().is_some());
+
+    Menu::new(LocalizedString::new("common-menu-edit-menu"))
+        .entry(undo)
+        .entry(redo)
+}
+
+pub fn build(_window: Option<WindowId>, _data: &AppData, _env: &Env) -> Menu<AppData> {
+    // macOS expects the application menu first
+    #[cfg(target_os = "macos")]
+    let menu = Menu::empty().entry(druid::platform_menus::mac::application::default());
+    #[cfg(not(target_os = "macos"))]
+    let menu = Menu::empty();
+    menu.entry(edit_menu())
+}
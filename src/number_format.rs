@@ -0,0 +1,82 @@
+use druid::text::{Formatter, Selection, Validation, ValidationError};
+use std::fmt;
+
+#[derive(Debug)]
+pub struct InvalidNumber;
+
+impl fmt::Display for InvalidNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not a finite number")
+    }
+}
+
+impl std::error::Error for InvalidNumber {}
+
+// Numbers are shown to four places and must parse to a value that is finite as an f32,
+// which is then clamped to the range if one is set. Partial input such as "-" or "1e" is
+// allowed while typing.
+#[derive(Default)]
+pub struct NumberFormatter {
+    range: Option<(f64, f64)>,
+}
+
+impl NumberFormatter {
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+}
+
+impl Formatter<f64> for NumberFormatter {
+    fn format(&self, value: &f64) -> String {
+        format!("{:.4}", value)
+    }
+
+    fn format_for_editing(&self, value: &f64) -> String {
+        value.to_string()
+    }
+
+    fn validate_partial_input(&self, input: &str, _sel: &Selection) -> Validation {
+        if input
+            .chars()
+            .all(|c| c.is_ascii_digit() || "+-.eE".contains(c))
+        {
+            Validation::success()
+        } else {
+            Validation::failure(InvalidNumber)
+        }
+    }
+
+    fn value(&self, input: &str) -> Result<f64, ValidationError> {
+        match input.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() && (value as f32).is_finite() => {
+                Ok(self.range.map_or(value, |(min, max)| value.clamp(min, max)))
+            }
+            _ => Err(ValidationError::new(InvalidNumber)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod number_format_tests {
+    use crate::number_format::NumberFormatter;
+    use druid::text::Formatter;
+
+    #[test]
+    fn values() {
+        let formatter = NumberFormatter::default();
+        assert_eq!(formatter.value("0.25").ok(), Some(0.25));
+        assert_eq!(formatter.value(" -1e2 ").ok(), Some(-100.));
+
+        // Anything that isn't a finite f32 is rejected
+        for input in ["", "-", "1.2.3", "inf", "NaN", "1e39"] {
+            assert!(formatter.value(input).is_err(), "{}", input);
+        }
+
+        let clamped = NumberFormatter::default().with_range(0., 0.5);
+        assert_eq!(clamped.value("0.25").ok(), Some(0.25));
+        assert_eq!(clamped.value("2").ok(), Some(0.5));
+        assert_eq!(clamped.value("-1").ok(), Some(0.));
+        assert!(clamped.value("inf").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use druid::lens::Map;
+use druid::widget::{
+    Checkbox, CrossAxisAlignment, Flex, Label, Painter, RadioGroup, Slider, TextBox,
+};
+use druid::{Color, Env, EventCtx, Lens, LensExt, PaintCtx, RenderContext, Widget, WidgetExt};
+
+use crate::app_data::{AppData, OffsetSide, OffsetStyle};
+use crate::history::Parameter;
+use crate::interaction::Interaction;
+use crate::number_format::NumberFormatter;
+
+// Colours a swatch cycles through when clicked
+const PALETTE: [Color; 8] = [
+    Color::rgb8(0, 128, 128),
+    Color::rgb8(200, 90, 0),
+    Color::rgb8(120, 60, 180),
+    Color::rgb8(30, 90, 200),
+    Color::rgb8(200, 30, 60),
+    Color::rgb8(40, 150, 40),
+    Color::rgb8(160, 130, 0),
+    Color::rgb8(90, 90, 90),
+];
+
+// Largest offset distance, the end of the slider's range
+const MAX_OFFSET: f64 = 0.5;
+
+// The offset distance, changed through the history so edits can be undone. A slider drag
+// or a run of typing undoes in one step.
+fn offset_lens() -> impl Lens<AppData, f64> {
+    Map::new(
+        |data: &AppData| data.offset,
+        |data: &mut AppData, offset: f64| {
+            if offset.is_finite() {
+                data.set_parameter_merged(Parameter::Offset, offset.clamp(0., MAX_OFFSET))
+            }
+        },
+    )
+}
+
+// A clickable colour swatch stepping through the palette
+fn swatch() -> impl Widget<Color> {
+    Painter::new(|ctx: &mut PaintCtx, color: &Color, _env: &Env| {
+        let rect = ctx.size().to_rect();
+        ctx.fill(rect, color);
+        ctx.stroke(rect, &Color::GRAY, 1.);
+    })
+    .fix_size(14., 14.)
+    .on_click(|_ctx: &mut EventCtx, color: &mut Color, _env: &Env| {
+        let i = PALETTE
+            .iter()
+            .position(|c| *c == *color)
+            .map_or(0, |i| i + 1);
+        *color = PALETTE[i % PALETTE.len()];
+    })
+}
+
+// Enable toggle and colour for one offset algorithm
+fn algorithm_row(
+    name: &str,
+    style: impl Lens<AppData, OffsetStyle> + Clone + 'static,
+) -> impl Widget<AppData> {
+    Flex::row()
+        .with_child(swatch().lens(style.clone().then(OffsetStyle::color)))
+        .with_spacer(6.)
+        .with_child(Checkbox::new(name).lens(style.then(OffsetStyle::enabled)))
+}
+
+pub fn build() -> impl Widget<AppData> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("Offset").with_text_size(16.))
+        .with_spacer(8.)
+        .with_child(
+            Slider::new()
+                .with_range(0., MAX_OFFSET)
+                .lens(offset_lens())
+                .controller(Interaction::default())
+                .expand_width(),
+        )
+        .with_child(
+            Flex::row()
+                .with_child(Label::new("Distance").fix_width(70.))
+                .with_flex_child(
+                    TextBox::new()
+                        .with_formatter(NumberFormatter::default().with_range(0., MAX_OFFSET))
+                        .update_data_while_editing(true)
+                        .lens(offset_lens())
+                        .controller(Interaction::default())
+                        .expand_width(),
+                    1.,
+                ),
+        )
+        .with_spacer(8.)
+        .with_child(algorithm_row("Levien", AppData::levien))
+        .with_child(algorithm_row("Tiller", AppData::tiller))
+        .with_child(algorithm_row("Klass", AppData::klass))
+        .with_spacer(8.)
+        .with_child(Label::new("Side"))
+        .with_child(
+            RadioGroup::column(vec![
+                ("Left", OffsetSide::Left),
+                ("Right", OffsetSide::Right),
+                ("Both", OffsetSide::Both),
+            ])
+            .lens(AppData::offset_side),
+        )
+        .padding(8.)
+        .fix_width(220.)
+}
@@ -0,0 +1,227 @@
+use druid::lens::Map;
+use druid::widget::{
+    Button, Controller, CrossAxisAlignment, Flex, Label, Scroll, TextBox, ViewSwitcher,
+};
+use druid::{Application, Color, Env, EventCtx, Lens, UpdateCtx, Widget, WidgetExt};
+
+use crate::app_data::AppData;
+use crate::interaction::Interaction;
+use crate::math::basis::Basis;
+use crate::math::vec2::Vec2;
+use crate::number_format::NumberFormatter;
+
+// Names of the four handles in each control point representation
+fn handle_name(basis: Basis, i: usize) -> &'static str {
+    match basis {
+        Basis::Hermite => ["P0", "P1", "T0", "T1"][i],
+        _ => ["P0", "P1", "P2", "P3"][i],
+    }
+}
+
+fn handle(data: &AppData, s: usize, i: usize) -> Option<Vec2<f32>> {
+    data.spline
+        .segments
+        .get(s)
+        .map(|segment| segment.control_handles(data.control_style)[i])
+}
+
+// Move handle (s, i) as an undoable edit, selecting it. Edits merge into one step for as
+// long as the same field keeps focus.
+fn set_handle(data: &mut AppData, s: usize, i: usize, position: Vec2<f32>) {
+    if handle(data, s, i).is_none_or(|p| p == position) {
+        return;
+    }
+    let before = data.spline.clone();
+    data.spline.move_handle(s, i, data.control_style, position);
+    data.record_merged(
+        &format!("Edit segment {} {}", s, handle_name(data.control_style, i)),
+        before,
+    );
+    data.selection = Some((s, i));
+}
+
+// One coordinate of handle (s, i), x for axis 0 and y for axis 1
+fn coordinate(s: usize, i: usize, axis: usize) -> impl Lens<AppData, f64> {
+    Map::new(
+        move |data: &AppData| {
+            handle(data, s, i).map_or(0., |p| f64::from(if axis == 0 { p.x } else { p.y }))
+        },
+        move |data: &mut AppData, value: f64| {
+            // The lens writes back on every event, so only real changes are applied
+            if let Some(mut p) = handle(data, s, i) {
+                if axis == 0 {
+                    p.x = value as f32;
+                } else {
+                    p.y = value as f32;
+                }
+                set_handle(data, s, i, p);
+            }
+        },
+    )
+}
+
+// Parse "x, y" pairs, one per line, separated by commas, tabs or spaces
+fn parse_points(text: &str) -> Vec<Vec2<f32>> {
+    text.lines()
+        .filter_map(|line| {
+            let mut values = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|part| !part.is_empty())
+                .map(|part| part.parse::<f32>().ok().filter(|v| v.is_finite()));
+            match (values.next(), values.next(), values.next()) {
+                (Some(Some(x)), Some(Some(y)), None) => Some(Vec2::new(x, y)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// Copy the selected row, or every row with nothing selected, as tab separated lines
+fn copy_rows(data: &AppData) {
+    let rows: Vec<(usize, usize)> = match data.selection {
+        Some(selected) => vec![selected],
+        None => (0..data.spline.len())
+            .flat_map(|s| (0..4).map(move |i| (s, i)))
+            .collect(),
+    };
+    let text: Vec<String> = rows
+        .into_iter()
+        .filter_map(|(s, i)| handle(data, s, i))
+        .map(|p| format!("{}\t{}", p.x, p.y))
+        .collect();
+    Application::global()
+        .clipboard()
+        .put_string(text.join("\n"));
+}
+
+// Paste points from the clipboard into consecutive rows, starting at the selected one
+fn paste_rows(data: &mut AppData) {
+    let text = match Application::global().clipboard().get_string() {
+        Some(text) => text,
+        None => return,
+    };
+    let (s_0, i_0) = data.selection.unwrap_or((0, 0));
+    let before = data.spline.clone();
+    for (k, p) in parse_points(&text).into_iter().enumerate() {
+        let (s, i) = (s_0 + (i_0 + k) / 4, (i_0 + k) % 4);
+        if s >= data.spline.len() {
+            break;
+        }
+        data.spline.move_handle(s, i, data.control_style, p);
+    }
+    data.record("Paste points", before);
+}
+
+// Scrolls a row into view when its handle becomes selected, such as from the canvas
+struct FollowSelection {
+    handle: (usize, usize),
+}
+
+impl<W: Widget<AppData>> Controller<AppData, W> for FollowSelection {
+    fn update(
+        &mut self,
+        child: &mut W,
+        ctx: &mut UpdateCtx,
+        old_data: &AppData,
+        data: &AppData,
+        env: &Env,
+    ) {
+        if data.selection == Some(self.handle) && old_data.selection != data.selection {
+            ctx.scroll_to_view();
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+fn point_row(s: usize, i: usize) -> impl Widget<AppData> {
+    // The row's name marks and sets the canvas selection
+    let name = Label::dynamic(move |data: &AppData, _env: &Env| {
+        let marker = if data.selection == Some((s, i)) {
+            ">"
+        } else {
+            " "
+        };
+        format!("{} {}", marker, handle_name(data.control_style, i))
+    })
+    .with_text_color(Color::grey(0.2))
+    .fix_width(36.)
+    .on_click(move |_ctx: &mut EventCtx, data: &mut AppData, _env: &Env| {
+        data.selection = Some((s, i))
+    });
+
+    let field = move |axis: usize| {
+        TextBox::new()
+            .with_formatter(NumberFormatter::default())
+            .update_data_while_editing(true)
+            .lens(coordinate(s, i, axis))
+            .controller(Interaction::default())
+            .expand_width()
+    };
+
+    Flex::row()
+        .with_child(name)
+        .with_flex_child(field(0), 1.)
+        .with_spacer(4.)
+        .with_flex_child(field(1), 1.)
+        .controller(FollowSelection { handle: (s, i) })
+}
+
+fn rows(segments: usize) -> impl Widget<AppData> {
+    let mut column = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+    for s in 0..segments {
+        column.add_child(Label::new(format!("Segment {}", s)).with_text_size(12.));
+        for i in 0..4 {
+            column.add_child(point_row(s, i));
+        }
+        column.add_spacer(4.);
+    }
+    column
+}
+
+pub fn build() -> impl Widget<AppData> {
+    let copy = Button::new("Copy")
+        .on_click(|_ctx: &mut EventCtx, data: &mut AppData, _env: &Env| copy_rows(data));
+    let paste = Button::new("Paste")
+        .on_click(|_ctx: &mut EventCtx, data: &mut AppData, _env: &Env| paste_rows(data));
+
+    // Rebuild the rows when segments are added or removed
+    let table = ViewSwitcher::new(
+        |data: &AppData, _env: &Env| data.spline.len(),
+        |segments: &usize, _data: &AppData, _env: &Env| Box::new(rows(*segments)),
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("Control points").with_text_size(16.))
+        .with_spacer(8.)
+        .with_child(
+            Flex::row()
+                .with_child(copy)
+                .with_spacer(4.)
+                .with_child(paste),
+        )
+        .with_spacer(8.)
+        .with_flex_child(Scroll::new(table).vertical(), 1.)
+        .padding(8.)
+        .fix_width(220.)
+}
+
+#[cfg(test)]
+mod point_table_tests {
+    use crate::math::vec2::Vec2;
+    use crate::point_table::parse_points;
+
+    #[test]
+    fn pasted_points() {
+        let text = "0.1, 0.2\n0.3\t0.4\n\n1 2 3\nx, 1\ninf, 1\n-5e-1,  7";
+        assert_eq!(
+            parse_points(text),
+            vec![
+                Vec2::new(0.1, 0.2),
+                Vec2::new(0.3, 0.4),
+                Vec2::new(-0.5, 7.)
+            ]
+        );
+        assert!(parse_points("").is_empty());
+    }
+}
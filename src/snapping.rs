@@ -0,0 +1,278 @@
+use druid::{Data, Lens};
+
+use crate::math::basis::Basis;
+use crate::math::path::Path;
+use crate::math::vec2::Vec2;
+
+// Angle increment for angle snapping, in degrees
+const SNAP_ANGLE: f32 = 15.;
+
+// Which snapping modes are enabled
+#[derive(Clone, Copy, Data, Lens, PartialEq)]
+pub struct SnapOptions {
+    pub grid: bool,
+    pub points: bool,
+    pub tangent: bool,
+    pub angle: bool,
+}
+
+impl Default for SnapOptions {
+    fn default() -> Self {
+        Self {
+            grid: false,
+            points: true,
+            tangent: true,
+            angle: false,
+        }
+    }
+}
+
+// What a dragged handle snapped to, kept for drawing an indicator
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Snap {
+    Grid(Vec2<f32>),
+    Point(Vec2<f32>),
+    // On the line through a joint along the neighbouring segment's tangent
+    Tangent {
+        joint: Vec2<f32>,
+        direction: Vec2<f32>,
+    },
+    // At a multiple of the snap angle about another handle
+    Angle {
+        anchor: Vec2<f32>,
+        degrees: f32,
+    },
+}
+
+// The handle that angle snapping measures from: the other end of a Bezier handle or
+// Hermite tangent, otherwise the neighbouring point of the control polygon
+fn adjacent_handle(basis: Basis, i: usize) -> usize {
+    match basis {
+        Basis::Bezier => [1, 0, 3, 2][i],
+        Basis::Hermite => [2, 3, 0, 1][i],
+        Basis::BSpline | Basis::CatmullRom => [1, 0, 1, 2][i],
+    }
+}
+
+// Handles that move along with handle (s, i): the handle itself, a Hermite tangent drawn
+// from it, and the handles of a joined neighbouring segment that follow the end it moves.
+// In the B-spline and Catmull-Rom bases every handle moves both ends of the segment.
+fn moving_handles(path: &Path, basis: Basis, s: usize, i: usize) -> Vec<(usize, usize)> {
+    let all = vec![0, 1, 2, 3];
+    let (own, previous_handles, next_handles) = match (basis, i) {
+        (Basis::Bezier, 0) => (vec![0], vec![3], vec![]),
+        (Basis::Bezier, 3) => (vec![3], vec![], vec![0]),
+        (Basis::Hermite, 0) => (vec![0, 2], vec![1, 3], vec![]),
+        (Basis::Hermite, 1) => (vec![1, 3], vec![], vec![0, 2]),
+        (Basis::BSpline | Basis::CatmullRom, _) => (vec![i], all.clone(), all),
+        _ => (vec![i], vec![], vec![]),
+    };
+
+    let p = &path.segments[s].control_points;
+    let (previous, next) = path.neighbours(s);
+    let previous = previous.filter(|q| path.segments[*q].control_points[3] == p[0]);
+    let next = next.filter(|q| path.segments[*q].control_points[0] == p[3]);
+
+    let mut handles: Vec<(usize, usize)> = own.into_iter().map(|j| (s, j)).collect();
+    if let Some(q) = previous {
+        handles.extend(previous_handles.into_iter().map(|j| (q, j)));
+    }
+    if let Some(q) = next {
+        handles.extend(next_handles.into_iter().map(|j| (q, j)));
+    }
+    handles
+}
+
+// The joint and tangent direction that handle i of segment s must line up with to keep
+// the path smooth, if the handle controls a tangent at a joint with a neighbour
+fn tangent_constraint(
+    path: &Path,
+    s: usize,
+    i: usize,
+    basis: Basis,
+) -> Option<(Vec2<f32>, Vec2<f32>)> {
+    let p = &path.segments[s].control_points;
+    let (previous, next) = path.neighbours(s);
+    let incoming = |q: usize| {
+        let q = &path.segments[q].control_points;
+        (q[3] == p[0]).then(|| (p[0], q[3] - q[2]))
+    };
+    let outgoing = |q: usize| {
+        let q = &path.segments[q].control_points;
+        (q[0] == p[3]).then(|| (p[3], q[1] - q[0]))
+    };
+    let (joint, direction) = match (basis, i) {
+        (Basis::Bezier, 1) | (Basis::Hermite, 2) => previous.and_then(incoming)?,
+        (Basis::Bezier, 2) => {
+            let (joint, direction) = next.and_then(outgoing)?;
+            (joint, -direction)
+        }
+        (Basis::Hermite, 3) => next.and_then(outgoing)?,
+        _ => return None,
+    };
+    Some((joint, direction.try_normalize()?))
+}
+
+// Snap the position of handle (s, i) being dragged to p. Candidates within tolerance (in
+// curve units) are tried in order of precedence: other points, tangent continuity, angle
+// increments, then the grid of the given spacing in x and y, which always snaps.
+pub fn snap(
+    path: &Path,
+    basis: Basis,
+    (s, i): (usize, usize),
+    p: Vec2<f32>,
+    options: &SnapOptions,
+    grid_step: Vec2<f32>,
+    tolerance: f32,
+) -> (Vec2<f32>, Option<Snap>) {
+    let handles = path.segments[s].control_handles(basis);
+
+    if options.points {
+        // Handles that move with the dragged one can't be snapped to
+        let moving = moving_handles(path, basis, s, i);
+        let nearest = path
+            .segments
+            .iter()
+            .enumerate()
+            .flat_map(|(r, segment)| {
+                let handles = segment.control_handles(basis);
+                (0..4).map(move |j| ((r, j), handles[j]))
+            })
+            .filter(|(key, _)| !moving.contains(key))
+            .map(|(_, q)| (q, q.distance(p)))
+            .filter(|(_, d)| *d < tolerance)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((q, _)) = nearest {
+            return (q, Some(Snap::Point(q)));
+        }
+    }
+
+    if options.tangent {
+        if let Some((joint, direction)) = tangent_constraint(path, s, i, basis) {
+            let q = joint + direction * (p - joint).dot(direction).max(0.);
+            if q.distance(p) < tolerance {
+                return (q, Some(Snap::Tangent { joint, direction }));
+            }
+        }
+    }
+
+    if options.angle {
+        let anchor = handles[adjacent_handle(basis, i)];
+        let v = p - anchor;
+        if v.mag() > 0. {
+            let angle = v.y.atan2(v.x).to_degrees();
+            let degrees = (angle / SNAP_ANGLE).round() * SNAP_ANGLE;
+            let q = anchor + Vec2::new(v.mag(), 0.).rotate(degrees.to_radians());
+            if q.distance(p) < tolerance {
+                return (q, Some(Snap::Angle { anchor, degrees }));
+            }
+        }
+    }
+
+    if options.grid && grid_step.x > 0. && grid_step.y > 0. {
+        let q = Vec2::new(
+            (p.x / grid_step.x).round() * grid_step.x,
+            (p.y / grid_step.y).round() * grid_step.y,
+        );
+        return (q, Some(Snap::Grid(q)));
+    }
+
+    (p, None)
+}
+
+#[cfg(test)]
+mod snapping_tests {
+    use crate::math::basis::Basis;
+    use crate::math::fixtures::curve;
+    use crate::math::path::Path;
+    use crate::math::vec2::Vec2;
+    use crate::snapping::{snap, Snap, SnapOptions};
+
+    const NONE: SnapOptions = SnapOptions {
+        grid: false,
+        points: false,
+        tangent: false,
+        angle: false,
+    };
+
+    // Two segments joined at (1, 0), the first ending along the x axis
+    fn path() -> Path {
+        Path::new(
+            vec![
+                curve([(0., 0.), (0.3, 0.4), (0.6, 0.), (1., 0.)]),
+                curve([(1., 0.), (1.3, 0.3), (1.7, 0.3), (2., 0.)]),
+            ],
+            false,
+        )
+    }
+
+    fn snap_to(
+        path: &Path,
+        handle: (usize, usize),
+        p: Vec2<f32>,
+        options: SnapOptions,
+    ) -> (Vec2<f32>, Option<Snap>) {
+        let step = Vec2::new(0.25, 0.25);
+        snap(path, Basis::Bezier, handle, p, &options, step, 0.05)
+    }
+
+    #[test]
+    fn points() {
+        let options = SnapOptions {
+            points: true,
+            ..NONE
+        };
+        let target = Vec2::new(0.6, 0.);
+        let (q, kind) = snap_to(&path(), (1, 2), Vec2::new(0.61, 0.01), options);
+        assert_eq!((q, kind), (target, Some(Snap::Point(target))));
+
+        // Once the handle sits on the point, the next move still snaps to it
+        let mut snapped = path();
+        snapped.segments[1].control_points[2] = target;
+        let (q, _) = snap_to(&snapped, (1, 2), Vec2::new(0.605, 0.003), options);
+        assert_eq!(q, target);
+
+        // The neighbour's end moves with a dragged joint, so it isn't a target
+        let p = Vec2::new(1.01, 0.);
+        assert_eq!(snap_to(&path(), (0, 3), p, options), (p, None));
+    }
+
+    #[test]
+    fn tangents_and_angles() {
+        let options = SnapOptions {
+            tangent: true,
+            ..NONE
+        };
+        let (q, kind) = snap_to(&path(), (1, 1), Vec2::new(1.3, 0.02), options);
+        assert!(q.approx_eq(Vec2::new(1.3, 0.), 1e-6));
+        assert_eq!(
+            kind,
+            Some(Snap::Tangent {
+                joint: Vec2::new(1., 0.),
+                direction: Vec2::new(1., 0.),
+            })
+        );
+
+        // A Bezier handle turns about its end point in 15 degree steps
+        let options = SnapOptions {
+            angle: true,
+            ..NONE
+        };
+        let p = Vec2::new(0.5, 0.).rotate(44_f32.to_radians());
+        let (q, kind) = snap_to(&path(), (0, 1), p, options);
+        assert!(q.approx_eq(Vec2::new(0.5, 0.).rotate(45_f32.to_radians()), 1e-5));
+        assert!(matches!(kind, Some(Snap::Angle { degrees, .. }) if degrees == 45.));
+    }
+
+    #[test]
+    fn grid() {
+        let options = SnapOptions { grid: true, ..NONE };
+        let (q, kind) = snap_to(&path(), (0, 1), Vec2::new(0.26, 0.74), options);
+        assert!(q.approx_eq(Vec2::new(0.25, 0.75), 1e-6));
+        assert!(matches!(kind, Some(Snap::Grid(_))));
+
+        // With every mode off the point is left alone
+        let p = Vec2::new(0.26, 0.74);
+        assert_eq!(snap_to(&path(), (0, 1), p, NONE), (p, None));
+    }
+}
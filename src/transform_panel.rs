@@ -0,0 +1,81 @@
+use druid::widget::{Button, Checkbox, CrossAxisAlignment, Flex, Label, TextBox};
+use druid::{Env, EventCtx, Lens, LensExt, Widget, WidgetExt};
+
+use crate::app_data::{AppData, TransformParams};
+use crate::number_format::NumberFormatter;
+
+// A labelled numeric entry for one transform parameter
+fn number_row(
+    label: &str,
+    lens: impl Lens<TransformParams, f64> + 'static,
+) -> impl Widget<AppData> {
+    Flex::row()
+        .with_child(Label::new(label).fix_width(70.))
+        .with_flex_child(
+            TextBox::new()
+                .with_formatter(NumberFormatter::default())
+                .update_data_while_editing(true)
+                .lens(AppData::transform.then(lens))
+                .expand_width(),
+            1.,
+        )
+}
+
+// Summarize the pending linear map as rotation, scale and skew
+fn describe(data: &AppData, _env: &Env) -> String {
+    match data.transform.matrix().decompose() {
+        Some(parts) => format!(
+            "Rotation {:.1}°, skew {:.1}°\nScale {:.3} x {:.3}",
+            parts.rotation.to_degrees(),
+            parts.skew.to_degrees(),
+            parts.scale_x,
+            parts.scale_y
+        ),
+        None => "Singular: flattens the curve\nonto a line or a point".to_string(),
+    }
+}
+
+pub fn build() -> impl Widget<AppData> {
+    let apply = Button::new("Apply")
+        .on_click(|_ctx: &mut EventCtx, data: &mut AppData, _env: &Env| data.apply_transform());
+    let reset =
+        Button::new("Reset").on_click(|_ctx: &mut EventCtx, data: &mut AppData, _env: &Env| {
+            data.transform = TransformParams::identity()
+        });
+    let flip_h = Button::new("Flip H")
+        .on_click(|_ctx: &mut EventCtx, data: &mut AppData, _env: &Env| data.flip(true));
+    let flip_v = Button::new("Flip V")
+        .on_click(|_ctx: &mut EventCtx, data: &mut AppData, _env: &Env| data.flip(false));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("Transform").with_text_size(16.))
+        .with_spacer(8.)
+        .with_child(Checkbox::new("Gizmo (t)").lens(AppData::transform_mode))
+        .with_spacer(8.)
+        .with_child(number_row("Move x", TransformParams::translate_x))
+        .with_child(number_row("Move y", TransformParams::translate_y))
+        .with_child(number_row("Rotate °", TransformParams::rotation))
+        .with_child(number_row("Scale x", TransformParams::scale_x))
+        .with_child(number_row("Scale y", TransformParams::scale_y))
+        .with_child(number_row("Skew x °", TransformParams::skew_x))
+        .with_child(number_row("Skew y °", TransformParams::skew_y))
+        .with_spacer(4.)
+        .with_child(Label::dynamic(describe).with_text_size(11.))
+        .with_spacer(8.)
+        .with_child(
+            Flex::row()
+                .with_child(apply)
+                .with_spacer(4.)
+                .with_child(reset),
+        )
+        .with_spacer(4.)
+        .with_child(
+            Flex::row()
+                .with_child(flip_h)
+                .with_spacer(4.)
+                .with_child(flip_v),
+        )
+        .padding(8.)
+        .fix_width(220.)
+}
@@ -0,0 +1,96 @@
+use druid::{Point, Rect};
+
+use crate::math::vec2::Vec2;
+
+// Map between curve space and the plot area on screen. Curve y points up and screen y
+// points down, so the map flips vertically.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewTransform {
+    pub screen: Rect,
+    pub curve: Rect,
+}
+
+impl ViewTransform {
+    // Fit the curve viewport into the screen rectangle. With equal_aspect the screen
+    // rectangle is letterboxed about its centre so that a unit in x spans as many pixels as
+    // a unit in y; otherwise each axis is stretched to fill it.
+    pub fn new(screen: Rect, curve: Rect, equal_aspect: bool) -> Self {
+        if !equal_aspect || curve.width() <= 0. || curve.height() <= 0. {
+            return Self { screen, curve };
+        }
+        let scale = (screen.width() / curve.width()).min(screen.height() / curve.height());
+        Self {
+            screen: Rect::from_center_size(
+                screen.center(),
+                (curve.width() * scale, curve.height() * scale),
+            ),
+            curve,
+        }
+    }
+
+    // Screen pixels per curve unit along x and y. An axis where either rectangle is empty,
+    // as before the first layout, maps one to one instead of dividing by zero.
+    pub fn scale(&self) -> (f64, f64) {
+        let ratio = |screen: f64, curve: f64| {
+            if screen > 0. && curve > 0. {
+                screen / curve
+            } else {
+                1.
+            }
+        };
+        (
+            ratio(self.screen.width(), self.curve.width()),
+            ratio(self.screen.height(), self.curve.height()),
+        )
+    }
+
+    pub fn to_screen(&self, p: &Vec2<f32>) -> Point {
+        let (s_x, s_y) = self.scale();
+        Point::new(
+            self.screen.x0 + (p.x as f64 - self.curve.x0) * s_x,
+            self.screen.y1 - (p.y as f64 - self.curve.y0) * s_y,
+        )
+    }
+
+    pub fn to_curve(&self, p: Point) -> Vec2<f32> {
+        let (s_x, s_y) = self.scale();
+        Vec2::new(
+            (self.curve.x0 + (p.x - self.screen.x0) / s_x) as f32,
+            (self.curve.y0 + (self.screen.y1 - p.y) / s_y) as f32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod view_transform_tests {
+    use crate::math::vec2::Vec2;
+    use crate::view_transform::ViewTransform;
+    use druid::{Point, Rect};
+
+    #[test]
+    fn round_trips() {
+        let screen = Rect::new(10., 20., 410., 220.);
+        let curve = Rect::new(-1., 0., 1., 2.);
+
+        // A 2x2 viewport in a 400x200 screen is letterboxed to 200x200 about the centre
+        let view = ViewTransform::new(screen, curve, true);
+        assert_eq!(view.screen, Rect::new(110., 20., 310., 220.));
+        assert_eq!(view.scale(), (100., 100.));
+        assert_eq!(view.to_screen(&Vec2::new(-1., 0.)), Point::new(110., 220.));
+        assert_eq!(view.to_screen(&Vec2::new(1., 2.)), Point::new(310., 20.));
+
+        // Without equal aspect each axis stretches to fill the screen
+        let stretched = ViewTransform::new(screen, curve, false);
+        assert_eq!(stretched.scale(), (200., 100.));
+
+        for view in [view, stretched] {
+            let p = Vec2::new(0.3, 1.7);
+            assert!(view.to_curve(view.to_screen(&p)).approx_eq(p, 1e-6));
+        }
+
+        // Before layout the rectangles are empty, and points still map to finite values
+        let empty = ViewTransform::new(Rect::ZERO, Rect::ZERO, false);
+        let p = empty.to_curve(Point::new(5., 5.));
+        assert!(p.x.is_finite() && p.y.is_finite());
+    }
+}